@@ -0,0 +1,97 @@
+//src\balance.rs
+use std::collections::BTreeSet;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::games::bet_amount;
+use crate::rounds::{build_rounds, sessions, RoundSettings, };
+use crate::storage::{load_records, number_at, value_at, Record, };
+
+/// Секция `balance` конфига игры: где баланс и зачисленный выигрыш транзакции
+#[derive(Debug, Clone, Default)]
+pub struct BalanceSettings {
+    pub field: String,
+    pub win_fields: Vec<String>, // выигрыш, зачисленный этой транзакцией (суммируется)
+    pub tolerance: f64,
+}
+
+impl BalanceSettings {
+    pub fn from_game_config(game_config: &GameConfig) -> Option<Self> {
+        let balance = game_config.balance.as_ref()?;
+        Some(BalanceSettings {
+            field: balance.field.clone(),
+            win_fields: balance.win.clone(),
+            tolerance: balance.tolerance.unwrap_or(0.005),
+        })
+    }
+}
+
+/// Расхождение баланса между двумя транзакциями сессии
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub session: String,
+    pub previous: usize, // индексы записей
+    pub current: usize,
+    pub before: f64,
+    pub bet: f64,
+    pub win: f64,
+    pub after: f64,
+}
+
+impl Violation {
+    pub fn expected(&self) -> f64 {
+        self.before - self.bet + self.win
+    }
+}
+
+/// Проверяет `after = before − bet + win` по сессиям; ставка списывается только первой транзакцией раунда
+pub fn reconcile(records: &[Record], game_config: &GameConfig, round_settings: &RoundSettings, settings: &BalanceSettings) -> (usize, Vec<Violation>) {
+    let paid: BTreeSet<usize> = build_rounds(records, game_config, round_settings).iter().map(|r| r.positions[0]).collect();
+    let mut checked = 0;
+    let mut violations = Vec::new();
+    for (session, positions) in sessions(records, round_settings) {
+        // (индекс записи с известным балансом, баланс), ставки и выигрыши, накопленные после неё
+        let mut last: Option<(usize, f64)> = None;
+        let (mut bet, mut win) = (0.0, 0.0);
+        for i in positions {
+            let tx = &records[i].value;
+            if paid.contains(&i) {bet += bet_amount(game_config, tx).unwrap_or(0.0);}
+            win += settings.win_fields.iter().filter_map(|path| number_at(tx, path)).sum::<f64>();
+            let Some(after) = number_at(tx, &settings.field) else {continue};
+            if let Some((previous, before)) = last {
+                checked += 1;
+                let violation = Violation {session: session.clone(), previous, current: i, before, bet, win, after};
+                if (violation.expected() - after).abs() > settings.tolerance {violations.push(violation);}
+            }
+            last = Some((i, after));
+            (bet, win) = (0.0, 0.0);
+        }
+    }
+    (checked, violations)
+}
+
+/// `balance <provider> <game>`: сверка баланса соседних транзакций каждой сессии
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    let settings = BalanceSettings::from_game_config(game_config).ok_or_else(|| format!("No 'balance.field' in {}", configs.game_path(provider_name, game_name).display()))?;
+    if game_config.bet.is_none() {return Err(format!("No 'bet' section in {}", configs.game_path(provider_name, game_name).display()).into());}
+    let round_settings = RoundSettings::from_game_config(game_config);
+
+    let mode_path = if let Some(mode) = args.option("mode") {format!("/bet_{mode}")} else {"".to_string()};
+    let records = load_records(format!("{location}/{provider_name}/{game_name}/transactions{mode_path}"));
+    let (checked, violations) = reconcile(&records, game_config, &round_settings, &settings);
+    for v in &violations {
+        println!("Session '{}': {}: balance {:.2} != {:.2} - {:.2} + {:.2} = {:.2} (previous balance at {})",
+            v.session, records[v.current].location(), v.after, v.before, v.bet, v.win, v.expected(), records[v.previous].location());
+    }
+    // баланс есть, но не число — такие записи в сверку не попадают, о них нужно знать
+    let unreadable: Vec<(&Record, String)> = records.iter().filter(|r| number_at(&r.value, &settings.field).is_none())
+        .filter_map(|r| value_at(&r.value, &settings.field).filter(|v| !v.is_null()).map(|v| (r, v.to_string()))).collect();
+    for (r, value) in unreadable.iter().take(10) {println!("{}: '{}' is not a number: {value}", r.location(), settings.field);}
+    if !unreadable.is_empty() {println!("Skipped {} records with an unreadable '{}'", unreadable.len(), settings.field);}
+    println!("Checked {checked} balance changes: {} violations", violations.len());
+    if !violations.is_empty() {return Err(format!("{} balance violations in {provider_name} game {game_name}", violations.len()).into());}
+    Ok(())
+}
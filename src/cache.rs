@@ -0,0 +1,101 @@
+//src\cache.rs
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Меняется вместе с форматом кэша: кэш старого формата просто перестаёт читаться
+const VERSION: u32 = 2;
+
+static DIR: OnceLock<String> = OnceLock::new();
+
+/// Включает кэш разобранных транзакций в папке `dir`; без вызова файлы всегда разбираются заново
+pub fn init(dir: String) {
+    let _ = DIR.set(dir);
+}
+
+/// По чему видно, что файл захвата не менялся
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stamp {
+    size: u64,
+    modified: (u64, u32), // секунды и наносекунды от UNIX_EPOCH
+    hash: u64,
+}
+
+/// Размер и время изменения файла
+fn metadata(path: &Path) -> Option<(u64, (u64, u32))> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), (modified.as_secs(), modified.subsec_nanos())))
+}
+
+impl Stamp {
+    pub fn new(path: &Path, content: &str) -> Option<Self> {
+        let (size, modified) = metadata(path)?;
+        Some(Stamp {size, modified, hash: hash(content.as_bytes())})
+    }
+
+    /// Размер и время изменения те же: файл можно не читать
+    pub fn unchanged(&self, path: &Path) -> bool {
+        metadata(path) == Some((self.size, self.modified))
+    }
+
+    /// Содержимое то же (файл, например, переписали без изменений)
+    pub fn same_content(&self, other: &Stamp) -> bool {
+        self.size == other.size && self.hash == other.hash
+    }
+}
+
+/// Записи одного файла захвата
+#[derive(Serialize, Deserialize)]
+pub struct Entry<V> {
+    pub stamp: Stamp,
+    pub records: Vec<V>,
+}
+
+/// Кэш одной папки транзакций: файл захвата -> его записи
+#[derive(Serialize, Deserialize)]
+struct Cache<V> {
+    version: u32,
+    root: String,
+    files: BTreeMap<String, Entry<V>>,
+}
+
+/// xxh3: в отличие от `DefaultHasher`, не меняется между версиями Rust, так что кэш переживает обновление компилятора
+fn hash(bytes: &[u8]) -> u64 {
+    xxh3_64(bytes)
+}
+
+/// Файл кэша папки: по хэшу её пути
+fn cache_path(dir: &str, root: &str) -> String {
+    format!("{dir}/{:016x}.msgpack", hash(root.as_bytes()))
+}
+
+pub fn enabled() -> bool {
+    DIR.get().is_some()
+}
+
+/// Записи, сохранённые для папки `root`; свежесть каждого файла проверяет вызывающий по `Stamp`
+pub fn open(root: &str) -> BTreeMap<String, Entry<Value>> {
+    let Some(dir) = DIR.get() else {return BTreeMap::new()};
+    let Ok(bytes) = fs::read(cache_path(dir, root)) else {return BTreeMap::new()};
+    match rmp_serde::from_slice::<Cache<Value>>(&bytes) {
+        Ok(cache) if cache.version == VERSION && cache.root == root => cache.files,
+        _ => BTreeMap::new(),
+    }
+}
+
+/// Перезаписывает кэш папки `root`; ошибки записи не мешают загрузке — в следующий раз файлы просто разберутся снова
+pub fn save(root: &str, files: BTreeMap<String, Entry<&Value>>) {
+    let Some(dir) = DIR.get() else {return};
+    let cache = Cache {version: VERSION, root: root.to_string(), files};
+    let Ok(bytes) = rmp_serde::to_vec_named(&cache) else {return};
+    let path = cache_path(dir, root);
+    // через временный файл: прерванная запись не оставит битый кэш
+    let temporary = format!("{path}.tmp");
+    if fs::create_dir_all(dir).is_ok() && fs::write(&temporary, bytes).is_ok() {let _ = fs::rename(&temporary, &path);}
+}
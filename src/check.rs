@@ -0,0 +1,85 @@
+//src\check.rs
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::rounds::{sessions, RoundSettings, };
+use crate::storage::{load_records, number_at, Record, };
+
+/// Найденная проблема записи
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Problem {
+    Duplicate,
+    Gap,
+    OutOfOrder,
+    MissingOut,
+}
+
+/// Секция `check` конфига игры: порядковые поля и шаг между соседними записями сессии (0 — только порядок)
+pub fn sequences(game_config: &GameConfig) -> Vec<(String, f64)> {
+    game_config.check.sequence.iter().map(|(path, step)| (path.clone(), *step)).collect()
+}
+
+/// Проверяет записи одной сессии (в порядке записи); возвращает (проблема, описание)
+pub fn check_session(records: &[&Record], sequences: &[(String, f64)]) -> Vec<(Problem, String)> {
+    let mut problems = Vec::new();
+    for record in records {
+        if record.value.get("in").is_some_and(|v| !v.is_null()) && record.value.get("out").is_none_or(|v| v.is_null()) {
+            problems.push((Problem::MissingOut, format!("{}: 'in' without 'out'", record.location())));
+        }
+    }
+    for (path, step) in sequences {
+        let values: Vec<(f64, &Record)> = records.iter().filter_map(|r| number_at(&r.value, path).map(|n| (n, *r))).collect();
+        let mut previous: Option<&(f64, &Record)> = None;
+        for current in &values {
+            if let Some(prev) = previous.filter(|p| current.0 < p.0) {
+                problems.push((Problem::OutOfOrder, format!("{}: {path} {} recorded after {} ({})", current.1.location(), current.0, prev.0, prev.1.location())));
+            }
+            if previous.is_none_or(|p| current.0 >= p.0) {previous = Some(current);}
+        }
+        // шаг 0: время и т.п., одинаковые значения и разрывы допустимы
+        if *step <= 0.0 {continue;}
+        let mut sorted = values.clone();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for pair in sorted.windows(2) {
+            let ((a, ra), (b, rb)) = (pair[0], pair[1]);
+            if a == b {
+                problems.push((Problem::Duplicate, format!("{}: {path} {b} already in {}", rb.location(), ra.location())));
+            } else if b - a > *step {
+                problems.push((Problem::Gap, format!("{}: {path} jumps from {a} to {b}, missing {} record(s) after {}", rb.location(), ((b - a) / step).round() as i64 - 1, ra.location())));
+            }
+        }
+    }
+    problems
+}
+
+/// `check <provider> <game>`: дубликаты, разрывы и нарушения порядка по сессиям, `in` без `out`
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    let settings = RoundSettings::from_game_config(game_config);
+    let sequences = sequences(game_config);
+    if sequences.is_empty() {println!("No 'check.sequence' in {}, only 'in' without 'out' is checked", configs.game_path(provider_name, game_name).display());}
+
+    let mode_path = if let Some(mode) = args.option("mode") {format!("/bet_{mode}")} else {"".to_string()};
+    let records = load_records(format!("{location}/{provider_name}/{game_name}/transactions{mode_path}"));
+    // порядок записи, а не `order`: иначе нарушения порядка не увидеть
+    let unordered = RoundSettings {order: Vec::new(), ..settings};
+    let sessions: Vec<(String, Vec<&Record>)> = sessions(&records, &unordered).into_iter().map(|(session, positions)| (session, positions.into_iter().map(|i| &records[i]).collect())).collect();
+
+    let mut counts = [0usize; 4];
+    for (session, items) in &sessions {
+        let mut problems = check_session(items, &sequences);
+        if problems.is_empty() {continue;}
+        problems.sort_by_key(|p| p.0);
+        println!("Session '{session}' ({} records):", items.len());
+        for (problem, message) in problems {
+            counts[problem as usize] += 1;
+            println!("\t{problem:?}: {message}");
+        }
+    }
+    println!("Checked {} records in {} sessions: {} duplicates, {} gaps, {} out of order, {} without 'out'", records.len(), sessions.len(), counts[0], counts[1], counts[2], counts[3]);
+    let total: usize = counts.iter().sum();
+    if total > 0 {return Err(format!("{total} problems found in {provider_name} game {game_name}").into());}
+    Ok(())
+}
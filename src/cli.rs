@@ -0,0 +1,72 @@
+//src\cli.rs
+use std::collections::BTreeMap;
+
+pub const USAGE: &str = "Usage:
+    converter [--config <dir|config.json>] <command> ...
+                                                   configs are searched in $CONVERTER_CONFIG, ./configs, next to the binary and in the XDG config dir
+    converter                                      interactive model generation
+    converter serve <provider> <game> [--mode <mode>] [--host <host>] [--port <port>] [--match sequence|best]
+    converter record <provider> <game> [--upstream http://host:port] [--host <host>] [--port <port>]
+    converter analyze <provider> <game> [--mode <mode>] [--export <file.json>]
+    converter rounds <provider> <game> [--mode <mode>] [--limit <n>]
+    converter actions <provider> <game> [--mode <mode>]
+    converter check <provider> <game> [--mode <mode>]
+    converter balance <provider> <game> [--mode <mode>]
+    converter redact <provider> <game> [--mode <mode>] [--output <dir>]
+    converter fixtures <provider> <game> [--mode <mode>] [--output <dir>] [--raw]
+    converter discover <provider> <game> [--write]
+    converter new-game <provider> <game> [--from <game>]
+    converter preset list | run <name>... | delete <name>
+    converter preset save <name> <provider> <game> [--mode <mode>] [--command <command>] [--action <action>] [--output <dir>]
+    converter watch <provider> <game> [--interval <seconds>]";
+
+/// Убирает глобальный `--config <path>` / `--config=<path>` из аргументов командной строки
+pub fn take_config(args: &mut Vec<String>) -> Option<String> {
+    let position = args.iter().position(|a| a == "--config" || a.starts_with("--config="))?;
+    let arg = args.remove(position);
+    match arg.strip_prefix("--config=") {
+        Some(path) => Some(path.to_string()),
+        None if position < args.len() => Some(args.remove(position)),
+        None => None,
+    }
+}
+
+/// Аргументы подкоманды: позиционные, `--key value` / `--key=value` и флаги `--key`
+#[derive(Debug, Default)]
+pub struct Args {
+    pub positional: Vec<String>,
+    pub options: BTreeMap<String, Option<String>>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> Self {
+        let mut parsed = Args::default();
+        let mut iter = args.iter().peekable();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if let Some((key, value)) = name.split_once('=') {
+                    parsed.options.insert(key.to_string(), Some(value.to_string()));
+                } else {
+                    let value = iter.next_if(|next| !next.starts_with("--")).cloned();
+                    parsed.options.insert(name.to_string(), value);
+                }
+            } else {
+                parsed.positional.push(arg.clone());
+            }
+        }
+        parsed
+    }
+
+    /// Обязательный позиционный аргумент
+    pub fn required(&self, index: usize, name: &str) -> Result<&str, Box<dyn std::error::Error + Send + Sync>> {
+        self.positional.get(index).map(|s| s.as_str()).ok_or_else(|| format!("Missing argument <{name}>\n{USAGE}").into())
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).and_then(|v| v.as_deref())
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+}
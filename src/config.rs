@@ -0,0 +1,508 @@
+//src\config.rs
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::convert_to_rust::EnumOverride;
+use crate::server::{MatchMode, ResponseFormat};
+
+/// Ошибка конфига: файл, ключ (`enums.paths.x.open`) и что с ним не так
+#[derive(Debug, Clone)]
+pub struct ConfigError {
+    pub file: String,
+    pub key: String,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub fn new(file: &Path, key: &str, message: impl Into<String>) -> Self {
+        ConfigError {file: file.display().to_string(), key: key.to_string(), message: message.into()}
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.key.is_empty() || self.key == "." {write!(f, "{}: {}", self.file, self.message)} else {write!(f, "{}: key '{}': {}", self.file, self.key, self.message)}
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Несколько ошибок сразу, чтобы не исправлять конфиги по одной
+#[derive(Debug, Clone)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for e in &self.0 {writeln!(f, "\t{e}")?;}
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Переменная окружения с путём к папке конфигов или к её `config.json`
+pub const CONFIG_ENV: &str = "CONVERTER_CONFIG";
+
+/// Общие настройки провайдера в папке его игр; накладываются под конфиг каждой игры провайдера
+pub const PROVIDER_DEFAULTS: &str = "_provider.json";
+
+/// Общий конфиг `configs/config.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default = "default_location")]
+    pub location: String, // относительный путь считается от папки конфигов, а не от текущей папки
+    #[serde(default)]
+    pub code: CodeSection,
+}
+
+fn default_location() -> String {
+    ".".to_string()
+}
+
+/// Секция `code`: общие ключи и переопределения для файлов `in` / `out`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CodeSection {
+    pub imports: Option<Vec<String>>,
+    pub struct_derives: Option<Vec<String>>,
+    pub struct_attributes: Option<Vec<String>>,
+    pub enum_derives: Option<Vec<String>>,
+    pub enum_attributes: Option<Vec<String>>,
+    pub tests: Option<usize>,
+    #[serde(rename = "in")]
+    pub in_: Option<Box<CodeSection>>,
+    pub out: Option<Box<CodeSection>>,
+}
+
+impl CodeSection {
+    pub fn side(&self, side: &str) -> Option<&CodeSection> {
+        match side {
+            "in" => self.in_.as_deref(),
+            "out" => self.out.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Конфиг игры `configs/games/{provider}/{game}.json`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GameConfig {
+    #[serde(default)]
+    pub modes: Vec<String>,
+    #[serde(default)]
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(default)]
+    pub selectors: Selectors,
+    #[serde(default)]
+    pub serve: ServeConfig,
+    pub bet: Option<BetConfig>,
+    #[serde(default)]
+    pub record: RecordConfig,
+    #[serde(default)]
+    pub rounds: RoundsConfig,
+    #[serde(default)]
+    pub stats: StatsConfig,
+    #[serde(default)]
+    pub check: CheckConfig,
+    pub balance: Option<BalanceConfig>,
+    pub redact: Option<RedactConfig>,
+    #[serde(default)]
+    pub skip_comments: Vec<String>,
+    #[serde(default)]
+    pub rename: Vec<String>,
+    #[serde(default)]
+    pub preserve_order: bool,
+    #[serde(default)]
+    pub enums: EnumsConfig,
+    #[serde(default)]
+    pub code: CodeSection,
+}
+
+/// Пути к command / action внутри транзакции
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Selectors {
+    pub command: Option<String>,
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServeConfig {
+    pub port: Option<u16>,
+    pub response_format: Option<ResponseFormat>,
+    #[serde(rename = "match")]
+    pub match_mode: Option<MatchMode>,
+}
+
+/// Ставка — произведение `fields`, режим — ставка × `multiplier`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BetConfig {
+    pub fields: Vec<String>,
+    #[serde(default = "default_multiplier")]
+    pub multiplier: f64,
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordConfig {
+    pub port: Option<u16>,
+    pub upstream: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoundsConfig {
+    #[serde(default)]
+    pub session: Vec<String>,
+    #[serde(default)]
+    pub order: Vec<String>,
+    #[serde(default)]
+    pub round_id: Vec<String>,
+    #[serde(default)]
+    pub start_actions: Vec<String>,
+    #[serde(default)]
+    pub continue_if_previous: Vec<String>,
+    #[serde(default)]
+    pub finished: BTreeMap<String, Value>, // одно значение или список
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatsConfig {
+    #[serde(default)]
+    pub win: WinConfig,
+    pub buckets: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WinConfig {
+    #[serde(default)]
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub cumulative: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CheckConfig {
+    #[serde(default)]
+    pub sequence: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceConfig {
+    pub field: String,
+    #[serde(default)]
+    pub win: Vec<String>,
+    pub tolerance: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactConfig {
+    #[serde(default)]
+    pub pseudonymize: Vec<String>,
+    #[serde(default)]
+    pub zero: Vec<String>,
+    #[serde(default)]
+    pub scale: Vec<String>,
+    pub factor: Option<f64>,
+    #[serde(default)]
+    pub on_load: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnumsConfig {
+    pub min_variants: Option<usize>,
+    pub max_variants: Option<usize>,
+    pub open: Option<bool>,
+    #[serde(default)]
+    pub paths: BTreeMap<String, EnumOverride>,
+}
+
+/// Все конфиги, загруженные и проверенные один раз при запуске
+#[derive(Debug, Clone)]
+pub struct Configs {
+    pub dir: PathBuf,
+    pub config: Config,
+    pub games: BTreeMap<String, Vec<String>>,
+    pub game_configs: BTreeMap<(String, String), GameConfig>,
+}
+
+impl Configs {
+    /// Папка конфигов: `--config`, затем `CONVERTER_CONFIG`, затем `configs/` в текущей папке, рядом с бинарником и `converter/` в XDG config
+    pub fn discover(explicit: Option<&str>) -> Result<PathBuf, String> {
+        let given = |path: &str, source: &str| -> Result<PathBuf, String> {
+            let path = Path::new(path);
+            if !path.exists() {return Err(format!("{source}: {} does not exist", path.display()));}
+            let dir = if path.is_dir() {path.to_path_buf()} else {path.parent().map(|p| p.to_path_buf()).unwrap_or_default()};
+            if dir.join("config.json").is_file() {Ok(dir)} else {Err(format!("{source}: no config.json in {}", dir.display()))}
+        };
+        if let Some(path) = explicit {return given(path, "--config");}
+        if let Ok(path) = std::env::var(CONFIG_ENV) && !path.is_empty() {return given(&path, CONFIG_ENV);}
+        let mut candidates = vec![PathBuf::from("configs")];
+        if let Some(exe_dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(|p| p.to_path_buf())) {candidates.push(exe_dir.join("configs"));}
+        if let Some(config_dir) = dirs::config_dir() {candidates.push(config_dir.join("converter"));}
+        candidates.iter().find(|dir| dir.join("config.json").is_file()).cloned().ok_or_else(|| {
+            let searched: Vec<String> = candidates.iter().map(|dir| format!("\t{}", dir.join("config.json").display())).collect();
+            format!("No config.json found, pass --config <dir|config.json> or set {CONFIG_ENV}. Searched:\n{}", searched.join("\n"))
+        })
+    }
+
+    /// Читает `config.json`, `games.json` и конфиг каждой игры из `games.json`; собирает все ошибки сразу
+    pub fn load(dir: &Path) -> Result<Configs, ConfigErrors> {
+        let mut errors = Vec::new();
+        let config = read_json::<Config>(&dir.join("config.json")).map_err(|e| errors.push(e)).ok().map(|mut config| {
+            config.location = dir.join(&config.location).display().to_string();
+            config
+        });
+        let games = read_json::<BTreeMap<String, Vec<String>>>(&dir.join("games.json")).map_err(|e| errors.push(e)).ok().unwrap_or_default();
+        let mut game_configs = BTreeMap::new();
+        for (provider, list) in &games {
+            let defaults_path = dir.join("games").join(provider).join(PROVIDER_DEFAULTS);
+            let defaults = if defaults_path.is_file() {
+                match read_value(&defaults_path) {
+                    Ok(defaults) => Some(defaults),
+                    Err(e) => {errors.push(e); continue;}
+                }
+            } else {None};
+            for game in list {
+                let path = dir.join("games").join(provider).join(format!("{game}.json"));
+                if !path.is_file() {
+                    errors.push(ConfigError::new(&dir.join("games.json"), provider,format!("game '{game}' is listed, but {} does not exist", path.display())));
+                    continue;
+                }
+                match load_game(&path, defaults.as_ref().map(|d| (defaults_path.as_path(), d))).and_then(|c| validate_game(&path, c)) {
+                    Ok(c) => {game_configs.insert((provider.clone(), game.clone()), c);}
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+        match config {
+            Some(config) if errors.is_empty() => Ok(Configs {dir: dir.to_path_buf(), config, games, game_configs}),
+            _ => Err(ConfigErrors(errors)),
+        }
+    }
+
+    /// Папка с данными (`location` из config.json)
+    pub fn location(&self) -> &str {
+        &self.config.location
+    }
+
+    pub fn game(&self, provider: &str, game: &str) -> Result<&GameConfig, String> {
+        self.game_configs.get(&(provider.to_string(), game.to_string()))
+            .ok_or_else(|| format!("Game '{game}' of provider '{provider}' is not listed in {}", self.dir.join("games.json").display()))
+    }
+
+    pub fn game_path(&self, provider: &str, game: &str) -> PathBuf {
+        self.dir.join("games").join(provider).join(format!("{game}.json"))
+    }
+}
+
+/// Читает JSON и десериализует его с путём до ключа в ошибке
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|e| ConfigError::new(path, "", format!("cannot read: {e}")))?;
+    let de = &mut serde_json::Deserializer::from_str(&text);
+    serde_path_to_error::deserialize(de).map_err(|e| ConfigError::new(path, &e.path().to_string(), e.inner().to_string()))
+}
+
+fn read_value(path: &Path) -> Result<Value, ConfigError> {
+    read_json::<Value>(path)
+}
+
+/// Конфиг игры поверх `_provider.json`; ошибка ключа, которого нет в конфиге игры, относится к файлу провайдера
+fn load_game(path: &Path, defaults: Option<(&Path, &Value)>) -> Result<GameConfig, ConfigError> {
+    let Some((defaults_path, defaults)) = defaults else {return read_json(path);};
+    let game = read_value(path)?;
+    let mut merged = defaults.clone();
+    merge(&mut merged, game.clone());
+    serde_path_to_error::deserialize(merged).map_err(|e| {
+        let key = e.path().to_string();
+        let file = if defines(&game, &key) {path} else {defaults_path};
+        ConfigError::new(file, &key, e.inner().to_string())
+    })
+}
+
+/// Накладывает overlay на base: объекты сливаются по ключам, остальное заменяется.
+/// Список можно не заменять, а изменить: `{"add": [...], "remove": [...]}` добавляет и убирает элементы списка провайдера
+pub fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        let mut value = value;
+                        if is_list_edit(&value) {let mut empty = Value::Array(Vec::new()); merge(&mut empty, value); value = empty;}
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), overlay) if is_list_edit(&overlay) => {
+            let list = |key: &str| overlay.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let remove = list("remove");
+            base.retain(|item| !remove.contains(item));
+            for item in list("add") {
+                if !base.contains(&item) {base.push(item);}
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Объект только с ключами `add` / `remove`, значения — списки
+fn is_list_edit(value: &Value) -> bool {
+    value.as_object().is_some_and(|map| !map.is_empty() && map.iter().all(|(key, v)| (key == "add" || key == "remove") && v.is_array()))
+}
+
+/// Есть ли в конфиге ключ по пути из ошибки десериализации (`rounds.finished`, `skip_comments[2]`)
+fn defines(value: &Value, key: &str) -> bool {
+    let mut current = value;
+    for segment in key.split('.').filter(|s| !s.is_empty() && *s != "?") {
+        let name = segment.split('[').next().unwrap_or_default();
+        if name.is_empty() {continue;}
+        match current.get(name) {
+            Some(next) => current = next,
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Заменяет значения ключей верхнего уровня в тексте JSON-конфига одной строкой `"key":value`, остальное форматирование не трогает.
+/// Ключа нет — он добавляется первой строкой объекта. Ошибка — ключи, которые так переписать не удалось (например, два ключа на одной строке)
+pub fn set_keys(text: &str, updates: &[(&str, Value)]) -> Result<String, String> {
+    let original: Value = serde_json::from_str(text).map_err(|e| format!("not valid JSON: {e}"))?;
+    let mut lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+    let mut failed: Vec<&str> = Vec::new();
+    for (key, value) in updates {
+        let prefix = format!("\"{key}\"");
+        let start = lines.iter().position(|l| l.trim_start().starts_with(&prefix) && l.trim_start()[prefix.len()..].trim_start().starts_with(':'));
+        match start {
+            Some(start) => {
+                let indent: String = lines[start].chars().take_while(|c| c.is_whitespace()).collect();
+                // значение может занимать несколько строк: берём строки, пока текст после ':' не станет JSON-значением
+                let mut end = start;
+                let mut comma = false;
+                while end < lines.len() {
+                    let joined = lines[start..=end].join("\n");
+                    let after = joined.split_once(':').map(|(_, v)| v.trim()).unwrap_or_default();
+                    let (candidate, trailing) = match after.strip_suffix(',') {Some(v) => (v, true), None => (after, false)};
+                    if serde_json::from_str::<Value>(candidate).is_ok() {comma = trailing; break;}
+                    end += 1;
+                }
+                if end == lines.len() {failed.push(key); continue;}
+                let line = format!("{indent}{prefix}:{value}{}", if comma {","} else {""});
+                lines.splice(start..=end, [line]);
+            }
+            // ключ есть, но не в начале строки: вставка дала бы дубликат
+            None if original.get(key).is_some() => failed.push(key),
+            None => {
+                let open = lines.iter().position(|l| l.trim() == "{").unwrap_or(0);
+                let has_more = lines.iter().skip(open + 1).any(|l| l.trim().starts_with('"'));
+                lines.insert(open + 1, format!("\t{prefix}:{value}{}", if has_more {","} else {""}));
+            }
+        }
+    }
+    let mut result = lines.join("\n");
+    if text.ends_with('\n') {result.push('\n');}
+    // проверка по разобранному результату: новые значения на месте, остальные ключи не задеты
+    let updated: Value = serde_json::from_str(&result).map_err(|e| format!("editing would break the JSON ({e}), edit it by hand"))?;
+    for (key, value) in updates {
+        if updated.get(key) != Some(value) && !failed.contains(key) {failed.push(key);}
+    }
+    if let Some(original) = original.as_object() {
+        for (key, value) in original {
+            if !updates.iter().any(|(k, _)| k == key) && updated.get(key) != Some(value) {failed.push(key);}
+        }
+    }
+    if !failed.is_empty() {return Err(format!("could not rewrite {}: each key must start its own line, edit it by hand", failed.iter().map(|k| format!("'{k}'")).collect::<Vec<_>>().join(", ")));}
+    Ok(result)
+}
+
+/// Проверки, которые не выразить типами
+fn validate_game(path: &Path, config: GameConfig) -> Result<GameConfig, ConfigError> {
+    if let Some(bet) = &config.bet && bet.fields.is_empty() {return Err(ConfigError::new(path, "bet.fields", "must list at least one field"));}
+    let (min, max) = (config.enums.min_variants.unwrap_or(2), config.enums.max_variants.unwrap_or(19));
+    if min > max {return Err(ConfigError::new(path, "enums", format!("min_variants {min} is greater than max_variants {max}")));}
+    for (key, paths) in [("selectors.command", config.selectors.command.iter().collect::<Vec<_>>()), ("selectors.action", config.selectors.action.iter().collect())] {
+        if paths.iter().any(|p| p.is_empty()) {return Err(ConfigError::new(path, key, "must not be empty"));}
+    }
+    if let Some(key) = config.rounds.finished.iter().find(|(_, values)| values.as_array().is_some_and(|a| a.is_empty())).map(|(key, _)| key) {
+        return Err(ConfigError::new(path, &format!("rounds.finished.{key}"), "must list at least one value"));
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_edits_provider_lists() {
+        let mut base = json!({"skip_comments": ["a", "b"], "rounds": {"start_actions": ["doSpin"]}});
+        merge(&mut base, json!({"skip_comments": {"add": ["c", "a"], "remove": ["b"]}, "rounds": {"start_actions": {"remove": ["doSpin"]}}}));
+        assert_eq!(base, json!({"skip_comments": ["a", "c"], "rounds": {"start_actions": []}}));
+    }
+
+    #[test]
+    fn merge_edit_without_provider_list_starts_from_empty() {
+        let mut base = json!({"rename": ["type"]});
+        merge(&mut base, json!({"skip_comments": {"add": ["win"], "remove": ["tw"]}, "actions": {"remove": ["doInit"]}}));
+        assert_eq!(base, json!({"rename": ["type"], "skip_comments": ["win"], "actions": []}));
+    }
+
+    #[test]
+    fn merge_replaces_lists_and_merges_objects() {
+        let mut base = json!({"modes": ["1"], "serve": {"port": 8080, "match": "sequence"}});
+        // объект с другими ключами — обычное значение, а не правка списка
+        merge(&mut base, json!({"modes": ["2"], "serve": {"match": "best"}, "selectors": {"add": "in.add"}}));
+        assert_eq!(base, json!({"modes": ["2"], "serve": {"port": 8080, "match": "best"}, "selectors": {"add": "in.add"}}));
+    }
+
+    #[test]
+    fn set_keys_replaces_values_and_keeps_the_rest_of_the_text() {
+        // многострочное значение заменяется одной строкой, пробелы в остальных ключах остаются
+        let text = "{\n\t\"modes\":[\"1\"],\n\t\"actions\":[\n\t\t\"doSpin\"\n\t],\n\t\"selectors\":{\"action\": \"in.action\"},\n\t\"serve\":{\"port\": 8081}\n}\n";
+        let result = set_keys(text, &[("modes", json!(["1", "100"])), ("actions", json!(["doInit", "doSpin"]))]).expect("keys are rewritten");
+        assert_eq!(result, "{\n\t\"modes\":[\"1\",\"100\"],\n\t\"actions\":[\"doInit\",\"doSpin\"],\n\t\"selectors\":{\"action\": \"in.action\"},\n\t\"serve\":{\"port\": 8081}\n}\n");
+    }
+
+    #[test]
+    fn set_keys_adds_missing_keys_first() {
+        let result = set_keys("{\n\t\"modes\":[]\n}", &[("commands", json!([]))]).expect("key is added");
+        assert_eq!(result, "{\n\t\"commands\":[],\n\t\"modes\":[]\n}");
+    }
+
+    #[test]
+    fn set_keys_fails_on_keys_it_cannot_rewrite() {
+        // два ключа на одной строке: заменить одну строку целиком нельзя
+        let text = "{\n\t\"modes\":[], \"actions\":[]\n}\n";
+        let error = set_keys(text, &[("actions", json!(["doSpin"]))]).expect_err("actions shares a line");
+        assert!(error.contains("'actions'"), "{error}");
+        // ключ в строке с `{`
+        let error = set_keys("{\"modes\":[],\n\t\"actions\":[]\n}", &[("modes", json!(["1"]))]).expect_err("modes is on the brace line");
+        assert!(error.contains("'modes'"), "{error}");
+        assert!(set_keys("not json", &[("modes", json!([]))]).is_err());
+    }
+}
@@ -2,6 +2,9 @@ use serde_json::Value;
 use std::collections::{BTreeMap, BTreeSet};
 use std::cmp::Ordering;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use crate::config::{CodeSection, Config, EnumsConfig, GameConfig, };
+use crate::redact::Redactor;
 
 /// Скалярные типы
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -41,38 +44,182 @@ struct StructDef {
 /// Описание enum (варианты хранятся как строки, подходит и для строковых, и для смешанных скалярных)
 struct EnumDef {
     name: String,
-    variants: BTreeSet<String>,
+    variants: Vec<String>,   // в порядке первого появления или по алфавиту (preserve_order)
     comment: String,
     is_string_enum: bool, // true — если enum из Vec<String>
+    open: bool,           // true — добавляется вариант Unknown(String) для незнакомых значений
+    default: Option<String>, // вариант по умолчанию для смешанных скалярных enum’ов
+}
+
+/// Переопределение настроек enum для конкретного поля
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnumOverride {
+    pub min_variants: Option<usize>,
+    pub max_variants: Option<usize>,
+    pub open: Option<bool>,
+}
+
+/// Настройки генерации строковых enum’ов (секция `enums` конфига игры)
+#[derive(Debug, Clone)]
+pub struct EnumSettings {
+    pub min_variants: usize,
+    pub max_variants: usize,
+    pub open: bool,
+    pub paths: BTreeMap<String, EnumOverride>, // путь поля (`context.round_type`) или имя поля -> переопределение
+}
+
+impl Default for EnumSettings {
+    fn default() -> Self {
+        EnumSettings {min_variants: 2, max_variants: 19, open: false, paths: BTreeMap::new()}
+    }
+}
+
+impl EnumSettings {
+    /// Настройки из секции `enums`, отсутствующие ключи берутся по умолчанию
+    pub fn from_config(enums: &EnumsConfig) -> Self {
+        let defaults = EnumSettings::default();
+        EnumSettings {
+            min_variants: enums.min_variants.unwrap_or(defaults.min_variants),
+            max_variants: enums.max_variants.unwrap_or(defaults.max_variants),
+            open: enums.open.unwrap_or(defaults.open),
+            paths: enums.paths.clone(),
+        }
+    }
+
+    /// Итоговые (min, max, open) для поля: полный путь важнее имени поля, имя поля важнее настроек игры
+    fn resolve(&self, path: &str, field: &str) -> (usize, usize, bool) {
+        let (mut min, mut max, mut open) = (self.min_variants, self.max_variants, self.open);
+        for key in [field, path] {
+            if let Some(o) = self.paths.get(key) {
+                if let Some(n) = o.min_variants {min = n;}
+                if let Some(n) = o.max_variants {max = n;}
+                if let Some(b) = o.open {open = b;}
+            }
+        }
+        (min, max, open)
+    }
+}
+
+/// Derive’ы, атрибуты и импорты генерируемого файла (секция `code`)
+#[derive(Debug, Clone)]
+pub struct CodeSettings {
+    pub imports: Vec<String>,
+    pub struct_derives: Vec<String>,
+    pub struct_attributes: Vec<String>,
+    pub enum_derives: Vec<String>, // Default здесь означает #[default] / impl Default, а не просто derive
+    pub enum_attributes: Vec<String>,
+    pub tests: usize, // сколько образцов встроить в сгенерированный round-trip тест; 0 — не генерировать
+}
+
+impl Default for CodeSettings {
+    fn default() -> Self {
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        CodeSettings {
+            imports: strings(&["use serde::{Serialize, Deserialize};", "use strum_macros::Display;"]),
+            struct_derives: strings(&["Debug", "Serialize", "Deserialize", "Default", "Clone"]),
+            struct_attributes: Vec::new(),
+            enum_derives: strings(&["Debug", "Serialize", "Deserialize", "Clone", "Display", "Default"]),
+            enum_attributes: Vec::new(),
+            tests: 0,
+        }
+    }
+}
+
+impl CodeSettings {
+    /// Накладывает секции `code` по порядку: общие ключи секции, затем ключи из `code.in` / `code.out` для side
+    pub fn from_sections(sections: &[&CodeSection], side: &str) -> Self {
+        let mut settings = CodeSettings::default();
+        for section in sections {
+            for layer in [Some(*section), section.side(side)].into_iter().flatten() {
+                if let Some(v) = &layer.imports {settings.imports = v.clone();}
+                if let Some(v) = &layer.struct_derives {settings.struct_derives = v.clone();}
+                if let Some(v) = &layer.struct_attributes {settings.struct_attributes = v.clone();}
+                if let Some(v) = &layer.enum_derives {settings.enum_derives = v.clone();}
+                if let Some(v) = &layer.enum_attributes {settings.enum_attributes = v.clone();}
+                if let Some(v) = layer.tests {settings.tests = v;}
+            }
+        }
+        settings
+    }
+
+    fn enum_default(&self) -> bool {
+        self.enum_derives.iter().any(|d| d == "Default")
+    }
+}
+
+/// Настройки генерации из конфига игры
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub skip_comments: Vec<String>,
+    pub rename: Vec<String>,
+    pub enums: EnumSettings,
+    pub code: CodeSettings,
+    pub preserve_order: bool, // поля и варианты enum в порядке данных, а не по алфавиту
+    pub redactor: Option<Redactor>, // redact игры для образцов тестов: сгенерированные модели попадают в репозиторий
+    pub side: String,               // `in` / `out`: под этим ключом образец лежит в транзакции для путей redact
+}
+
+impl Settings {
+    /// Читает `skip_comments`, `rename`, `enums` и `preserve_order` из конфига игры, `code` — из общего конфига и конфига игры для файла side (`in` / `out`)
+    pub fn from_game_config(config: &Config, game_config: &GameConfig, side: &str) -> Self {
+        Settings {
+            skip_comments: game_config.skip_comments.clone(),
+            rename: game_config.rename.clone(),
+            enums: EnumSettings::from_config(&game_config.enums),
+            code: CodeSettings::from_sections(&[&config.code, &game_config.code], side),
+            preserve_order: game_config.preserve_order,
+            redactor: Redactor::from_game_config(game_config),
+            side: side.to_string(),
+        }
+    }
+
+    /// Образцы без персональных данных: redact применяется к транзакции `{side: образец}`, как в `fixtures`
+    fn redact_samples(&self, samples: &[&Value]) -> Vec<Value> {
+        let Some(mut redactor) = self.redactor.clone() else {return samples.iter().map(|v| (*v).clone()).collect()};
+        samples.iter().map(|sample| {
+            let mut tx = Value::Object(serde_json::Map::from_iter([(self.side.clone(), (*sample).clone())]));
+            redactor.redact(&mut tx);
+            tx.get_mut(&self.side).map(Value::take).unwrap_or_default()
+        }).collect()
+    }
 }
 
 /// Контекст генерации
 pub struct Context {
     skip_comments: BTreeSet<String>,
     rename: BTreeSet<String>,
+    enum_settings: EnumSettings,
+    code: CodeSettings,
+    preserve_order: bool,
     structs: Vec<StructDef>,
     enums: Vec<EnumDef>,
     registry: BTreeMap<String, String>,      // signature -> struct name
     enum_registry: BTreeMap<String, String>, // signature -> enum name
+    type_names: BTreeSet<String>,            // уже занятые имена struct/enum
     progress:     Option<ProgressBar>,  // ← добавили
 }
 
 impl Context {
     /// Создаёт новый контекст
-    fn new(skip_comments: &[&str], rename: &[&str]) -> Self {
+    fn new(settings: &Settings) -> Self {
         Context {
-            skip_comments: skip_comments.iter().map(|s| s.to_string()).collect(),
-            rename: rename.iter().map(|s| s.to_string()).collect(),
+            skip_comments: settings.skip_comments.iter().cloned().collect(),
+            rename: settings.rename.iter().cloned().collect(),
+            enum_settings: settings.enums.clone(),
+            code: settings.code.clone(),
+            preserve_order: settings.preserve_order,
             structs: Vec::new(),
             enums: Vec::new(),
             registry: BTreeMap::new(),
             enum_registry: BTreeMap::new(),
+            type_names: BTreeSet::new(),
             progress:      None,
         }
     }
 
     /// Запускает анализ для корневой структуры
-    fn build_root(&mut self, root_name: &str, values: &[Value]) {
+    fn build_root(&mut self, root_name: &str, values: &[Value]) -> String {
         // Собираем set из всех ключей root-объектов, чтобы знать общее число полей
         let total_fields = values.iter().filter_map(Value::as_object).map(|m| {m.len()}).max().unwrap_or(100) as u64;
         // создаём единый прогресс-бар
@@ -80,56 +227,93 @@ impl Context {
         pb.set_prefix(root_name.to_string());
         self.progress = Some(pb.clone());
         // рекурсивно строим структуру
-        self.build_struct(root_name, values);
+        let name = self.build_struct(root_name, "", values);
         // завершаем бар
         pb.finish_with_message("Field analysis is completed!");
+        name
+    }
+
+    /// Что из модели задействует значение: структуры, optional поля (есть / нет), варианты enum’ов
+    fn collect_features(&self, struct_name: &str, value: &Value, out: &mut BTreeSet<String>) {
+        let Some(def) = self.structs.iter().find(|s| s.name == struct_name) else {return};
+        out.insert(format!("struct {}", def.name));
+        for field in &def.fields {
+            let v = value.get(&field.original_name).filter(|v| !v.is_null());
+            if field.optional {out.insert(format!("{}.{} {}", def.name, field.original_name, if v.is_some() {"present"} else {"absent"}));}
+            if let Some(v) = v {self.collect_type_features(&field.field_type, v, out);}
+        }
+    }
+
+    fn collect_type_features(&self, ft: &FieldType, value: &Value, out: &mut BTreeSet<String>) {
+        match ft {
+            FieldType::Object(name) => self.collect_features(name, value, out),
+            FieldType::Array(inner) => {
+                for item in value.as_array().into_iter().flatten() {self.collect_type_features(inner, item, out);}
+            }
+            FieldType::Enum(name) => {
+                let Some(e) = self.enums.iter().find(|e| &e.name == name) else {return};
+                let variant = match value {
+                    Value::String(s) if e.is_string_enum => s.as_str(),
+                    Value::String(_) => "String",
+                    Value::Bool(_) => "Bool",
+                    v if v.as_i64().is_some() => "Int",
+                    _ => "Float",
+                };
+                out.insert(format!("enum {}::{}", name, variant));
+            }
+            _ => {}
+        }
     }
 
-    /// Рекурсивно строит struct для объектов и регистрирует его
-    fn build_struct(&mut self, struct_name: &str, values: &[Value]) -> String {
+    /// Рекурсивно строит struct для объектов и регистрирует его (path — путь объекта от корня)
+    fn build_struct(&mut self, struct_name: &str, path: &str, values: &[Value]) -> String {
         let total = values.len();
-        let mut field_map: BTreeMap<&str, Vec<&Value>> = BTreeMap::new();
+        // поля в порядке первого появления в данных (или по алфавиту, если preserve_order выключен)
+        let mut field_map: Vec<(&str, Vec<&Value>)> = Vec::new();
+        let mut field_index: BTreeMap<&str, usize> = BTreeMap::new();
         for v in values {
             if let Value::Object(map) = v {
                 for (k, vchild) in map {
-                    field_map.entry(k.as_str()).or_default().push(vchild);
+                    let i = *field_index.entry(k.as_str()).or_insert_with(|| {field_map.push((k.as_str(), Vec::new())); field_map.len() - 1});
+                    field_map[i].1.push(vchild);
                 }
             }
         }
+        if !self.preserve_order {field_map.sort_by(|a, b| a.0.cmp(b.0));}
         let mut fields = Vec::new();
+        let mut field_names = BTreeSet::new();
         for (orig, vals) in field_map {
             // если общий прогресс-бар инициализирован — инкрементим его
             if let Some(pb) = &self.progress {pb.set_message(format!("Field processing `{}`", orig)); pb.inc(1);}
             let count = vals.len();
             let optional = count < total || vals.iter().any(|v| v.is_null());
-            let ftype = self.determine_field_type(struct_name, &orig, &vals);
-            let rust_name = self.compute_rust_name(struct_name, &orig);
-            let rename_attr = self.rename.contains(orig) || orig.chars().next().map_or(false, |c| c.is_numeric()) || rust_name != to_snake_case(&orig);
+            let field_path = if path.is_empty() {orig.to_string()} else {format!("{}.{}", path, orig)};
+            let ftype = self.determine_field_type(struct_name, orig, &field_path, &vals);
+            let rust_name = unique_name(&mut field_names, self.compute_rust_name(struct_name, orig), "_");
+            // serde видит имя без префикса r#, поэтому rename нужен при любом расхождении с исходным ключом
+            let rename_attr = rust_name.trim_start_matches("r#") != orig;
             // Вычисляем комментарий для скалярных полей, одномерных массивов скаляров и массивов enum’ов из Vec<String>
             let comment = match &ftype {
                 // 0) Любые многомерные массивы (размерность >1) — только первое значение
                 FieldType::Array(inner) if matches!(**inner, FieldType::Array(_)) => {
                     // vals здесь — &[Value], каждый из которых тоже Value::Array
-                    if let Some(Value::Array(_arr0)) = vals.get(0) {
+                    if let Some(Value::Array(_arr0)) = vals.first() {
                         // arr0 — Vec<Value>, берём его первый элемент
-                        if let Some(first_val) = vals.get(0) {
-                            // Просто to_string(), т.к. может быть любой Value
-                            Some(first_val.to_string())
-                        } else {None}
+                        vals.first().map(|first_val| first_val.to_string())
                     } else {None}
                 }
                 // 1) Обычные скалярные поля
                 FieldType::Scalar(_) => {
                     if self.skip_comments.contains(orig) {
                         // берём только первый элемент
-                        vals.get(0).map(|v| {if v.is_string() {format!("\"{}\"", v.as_str().unwrap())} else {v.to_string()}})
+                        vals.first().map(|v| {if v.is_string() {format!("\"{}\"", v.as_str().unwrap())} else {v.to_string()}})
                     } else {Some(unique_values_summary(&vals))}
                 } 
                 // 2) Одномерные массивы скалярных значений Vec<T>
                 FieldType::Array(inner) if matches!(**inner, FieldType::Scalar(_)) => {
                     if self.skip_comments.contains(orig) {
                         // только первый подмассив
-                        if let Some(Value::Array(arr)) = vals.get(0) {
+                        if let Some(Value::Array(arr)) = vals.first() {
                             let json_array = Value::Array(arr.clone());
                             Some(serde_json::to_string(&json_array).expect("serialization must succeed"))
                         } else {None}
@@ -164,7 +348,7 @@ impl Context {
                             if self.skip_comments.contains(orig) {
                                 // Только первый вариант из comment (разделённого запятыми)
                                 /*ed.comment.split(", ").next().map(|s| s.to_string())*/
-                                Some(vals.get(0).unwrap_or(&&Value::Null).to_string())
+                                Some(vals.first().unwrap_or(&&Value::Null).to_string())
                             } else {Some(ed.comment.clone())}
                         } else {None}
                     } else {None}
@@ -176,34 +360,28 @@ impl Context {
         }
         let sig = signature(&fields);
         if let Some(existing) = self.registry.get(&sig) {existing.clone()} else {
-            self.registry.insert(sig, struct_name.to_string());
-            self.structs.push(StructDef {name: struct_name.to_string(), fields,});
-            struct_name.to_string()
+            // одноимённые объекты с разной формой получают суффикс: Spins, Spins2, ...
+            let name = unique_name(&mut self.type_names, type_ident(struct_name), "");
+            self.registry.insert(sig, name.clone());
+            self.structs.push(StructDef {name: name.clone(), fields,});
+            name
         }
     }
 
     /// Определяет тип поля по списку значений
-    fn determine_field_type(&mut self, parent: &str, field: &str, vals: &[&Value]) -> FieldType {
+    fn determine_field_type(&mut self, parent: &str, field: &str, path: &str, vals: &[&Value]) -> FieldType {
         let non_null: Vec<&Value> = vals.iter().filter(|v| !v.is_null()).copied().collect();
         if non_null.is_empty() {return FieldType::Any;}
         if non_null.iter().all(|v| v.is_string()) {
             // Собираем уникальные строковые варианты
-            let vals_set: BTreeSet<String> = non_null.iter().filter_map(|v| v.as_str().map(String::from)).collect();
-            // Если уникальных значений от 2 до 9 включительно — делаем enum,
+            let vals_set = unique_strings(&non_null);
+            // Если число уникальных значений попадает в границы из настроек (по умолчанию 2..=19) — делаем enum,
             // иначе — оставляем String
-            if (2..=19).contains(&vals_set.len()) && !self.skip_comments.contains(field) {
-                // общая подпись
-                let sig = format!("StringEnum:{}", vals_set.iter().cloned().collect::<Vec<_>>().join("|"));
-                // реюз или создание нового enum-а
-                let enum_name = if let Some(name) = self.enum_registry.get(&sig) {name.clone()} else {
-                    let name = format!("{}{}Enum", to_upper_camel_case(parent), to_upper_camel_case(field));
-                    self.enum_registry.insert(sig.clone(), name.clone());
-                    self.enums.push(EnumDef {name: name.clone(), variants: vals_set.clone(), comment: vals_set.iter().cloned().collect::<Vec<_>>().join(", "), is_string_enum: true,});
-                    name
-                };
-                return FieldType::Enum(enum_name);
+            let (min, max, open) = self.enum_settings.resolve(path, field);
+            if (min..=max).contains(&vals_set.len()) && !self.skip_comments.contains(field) {
+                return FieldType::Enum(self.register_string_enum(parent, field, vals_set, open));
             } else {
-                // слишком мало (0–1) или слишком много (>=19) вариантов — просто String
+                // слишком мало или слишком много вариантов — просто String
                 return FieldType::Scalar(ScalarType::String);
             }
         }
@@ -222,27 +400,21 @@ impl Context {
                     for item in arr {elems.push(item);}
                 }
             }
-            // Особый случай: одномерный массив строк превращаем в enum
+            // Особый случай: одномерный массив строк превращаем в enum (с теми же границами, что и для строковых полей)
             if elems.iter().all(|v| v.is_string()) {
                 // Собираем уникальные строковые варианты
-                let variants: BTreeSet<String> = elems.iter().filter_map(|v| v.as_str().map(String::from)).collect();
-                // Используем **ту же** подпись
-                let sig = format!("StringEnum:{}",variants.iter().cloned().collect::<Vec<_>>().join("|"));
-                // Пытаемся переиспользовать
-                if let Some(enum_name) = self.enum_registry.get(&sig) {return FieldType::Array(Box::new(FieldType::Enum(enum_name.clone())));}
-                // Иначе создаём новый enum
-                let enum_name = format!("{}{}Enum", to_upper_camel_case(parent), to_upper_camel_case(field));
-                self.enum_registry.insert(sig.clone(), enum_name.clone());
-                self.enums.push(EnumDef {name: enum_name.clone(), variants: variants.clone(), comment: variants.iter().cloned().collect::<Vec<_>>().join(", "), is_string_enum: true,});
-                return FieldType::Array(Box::new(FieldType::Enum(enum_name)));
+                let variants = unique_strings(&elems);
+                let (min, max, open) = self.enum_settings.resolve(path, field);
+                if !(min..=max).contains(&variants.len()) {return FieldType::Array(Box::new(FieldType::Scalar(ScalarType::String)));}
+                return FieldType::Array(Box::new(FieldType::Enum(self.register_string_enum(parent, field, variants, open))));
             }
             // Обычная обработка вложенных массивов
-            let nested_field_name = if field.chars().next().map_or(false, |c| c.is_numeric()) {format!("{}_Elem", parent)} else {format!("{}", field)};
-            let inner = self.determine_field_type(parent, &nested_field_name, &elems);
+            let nested_field_name = if field.chars().next().is_some_and(|c| c.is_numeric()) {format!("{}_Elem", parent)} else {field.to_string()};
+            let inner = self.determine_field_type(parent, &nested_field_name, path, &elems);
             return FieldType::Array(Box::new(inner));
         }
         if non_null.iter().all(|v| v.is_object()) {
-            let nested_name = if field.chars().next().map_or(false, |c| c.is_numeric()) {
+            let nested_name = if field.chars().next().is_some_and(|c| c.is_numeric()) {
                 format!("{}Elem", parent)
             } else if self.rename.contains(field) {
                 // зарезервированные или переименованные поля → Parent + Field
@@ -251,57 +423,79 @@ impl Context {
                 to_upper_camel_case(field)
             };
             let nested_vals: Vec<Value> = non_null.into_iter().cloned().collect();
-            return FieldType::Object(self.build_struct(&nested_name, &nested_vals));
+            return FieldType::Object(self.build_struct(&nested_name, path, &nested_vals));
         }
         if non_null.iter().all(|v| v.is_string() || v.is_boolean() || v.is_number()) {
-            // Смешанные скалярные типы → enum с вариантами-строками
-            let mut set = BTreeSet::new();
+            // Смешанные скалярные типы → untagged enum только с реально встреченными вариантами
+            let mut counts: BTreeMap<ScalarType, usize> = BTreeMap::new();
             for v in &non_null {
-                if v.is_string() {
-                    set.insert(ScalarType::String);
+                let st = if v.is_string() {
+                    ScalarType::String
                 } else if v.is_boolean() {
-                    set.insert(ScalarType::Bool);
-                } else if v.is_number() {
-                    set.insert(ScalarType::Int);
-                    set.insert(ScalarType::Float);
-                }
+                    ScalarType::Bool
+                } else if v.as_i64().is_some() {
+                    ScalarType::Int
+                } else {
+                    ScalarType::Float
+                };
+                *counts.entry(st).or_default() += 1;
             }
+            // по умолчанию — самый частый из встреченных типов (при равенстве — первый по порядку ScalarType)
+            let default = counts.iter().fold(None, |best: Option<(&ScalarType, &usize)>, (st, n)| match best {
+                Some((_, bn)) if bn >= n => best,
+                _ => Some((st, n)),
+            }).map(|(st, _)| scalar_variant(st).to_string());
             // подпись для повторного использования одного enum
-            let mut codes: Vec<char> = set.iter().map(|st| match st {
-                ScalarType::Bool => 'B',
-                ScalarType::Int => 'I',
-                ScalarType::Float => 'F',
-                ScalarType::String => 'S',
-            }).collect();
-            codes.sort();
-            let sig = format!("E{}", codes.iter().collect::<String>());
+            let sig = format!("E{}:{}", counts.keys().map(scalar_variant).collect::<Vec<_>>().join("|"), default.clone().unwrap_or_default());
             // имя enum
             let enum_name = if let Some(n) = self.enum_registry.get(&sig) {n.clone()} else {
                 // создаём новый enum
-                let name = format!("{}{}Enum", to_upper_camel_case(parent), to_upper_camel_case(field));
+                let name = unique_name(&mut self.type_names, format!("{}{}Enum", to_upper_camel_case(parent), to_upper_camel_case(field)), "");
                 self.enum_registry.insert(sig.clone(), name.clone());
-                // конвертируем варианты ScalarType в строки
-                let mut variant_strs = BTreeSet::new();
-                for st in &set {
-                    let vstr = match st {
-                        ScalarType::Bool => "Bool".to_string(),
-                        ScalarType::Int => "Int".to_string(),
-                        ScalarType::Float => "Float".to_string(),
-                        ScalarType::String => "String".to_string(),
-                    };
-                    variant_strs.insert(vstr);
-                }
+                let variant_strs: Vec<String> = counts.keys().map(|st| scalar_variant(st).to_string()).collect();
                 let summary_vals: Vec<&Value> = non_null.clone();
-                self.enums.push(EnumDef { name: name.clone(), variants: variant_strs, comment: unique_values_summary(&summary_vals), is_string_enum: false, });
+                self.enums.push(EnumDef { name: name.clone(), variants: variant_strs, comment: unique_values_summary(&summary_vals), is_string_enum: false, open: false, default, });
                 name
             };
             return FieldType::Enum(enum_name);
         }
         FieldType::Any
     }
+    /// Регистрирует строковый enum (или переиспользует уже созданный с теми же вариантами) и возвращает его имя
+    fn register_string_enum(&mut self, parent: &str, field: &str, mut variants: Vec<String>, open: bool) -> String {
+        // общая подпись не зависит от порядка; открытый и закрытый enum с одинаковыми вариантами — разные типы
+        let mut sorted = variants.clone();
+        sorted.sort();
+        let sig = format!("{}StringEnum:{}", if open {"Open"} else {""}, sorted.join("|"));
+        if let Some(name) = self.enum_registry.get(&sig) {return name.clone();}
+        if !self.preserve_order {variants = sorted;}
+        let name = unique_name(&mut self.type_names, format!("{}{}Enum", to_upper_camel_case(parent), to_upper_camel_case(field)), "");
+        self.enum_registry.insert(sig, name.clone());
+        let comment = variants.join(", ");
+        self.enums.push(EnumDef {name: name.clone(), variants, comment, is_string_enum: true, open, default: None,});
+        name
+    }
     /// Вычисляет rust-имя поля
     fn compute_rust_name(&self, parent: &str, orig: &str) -> String {
-        if self.rename.contains(orig) || orig.chars().next().map_or(false, |c| c.is_numeric()) {format!("{}_{}", to_snake_case(parent), to_snake_case(orig))} else {to_snake_case(orig)}
+        if self.rename.contains(orig) || orig.chars().next().is_some_and(|c| c.is_numeric()) {format!("{}_{}", to_snake_case(parent), to_snake_case(orig))} else {field_ident(to_snake_case(orig))}
+    }
+    /// Типы, в которых (в том числе через вложенные структуры и enum’ы) есть f64: им нельзя derive FLOAT_INCOMPATIBLE
+    fn float_types(&self) -> BTreeSet<String> {
+        let mut floats: BTreeSet<String> = self.enums.iter().filter(|e| !e.is_string_enum && e.variants.iter().any(|v| v == "Float")).map(|e| e.name.clone()).collect();
+        fn has_float(ft: &FieldType, floats: &BTreeSet<String>) -> bool {
+            match ft {
+                FieldType::Scalar(st) => *st == ScalarType::Float,
+                FieldType::Enum(name) | FieldType::Object(name) => floats.contains(name),
+                FieldType::Array(inner) => has_float(inner, floats),
+                FieldType::Any => false,
+            }
+        }
+        // структура с f64 делает такой же и ту, что её содержит
+        loop {
+            let found: Vec<String> = self.structs.iter().filter(|s| !floats.contains(&s.name) && s.fields.iter().any(|f| has_float(&f.field_type, &floats))).map(|s| s.name.clone()).collect();
+            if found.is_empty() {return floats;}
+            floats.extend(found);
+        }
     }
     /// Генерирует итоговый код (enums + structs)
     fn generate_code(&self, generate_impl_from: bool, impl_source_object: String, enums_import_path: String, ) -> String {
@@ -310,23 +504,32 @@ impl Context {
         let pb = ProgressBar::new(total_tasks as u64).with_style(ProgressStyle::default_bar().template("{spinner:.green} [{elapsed_precise}] {prefix} [{bar:40.cyan/blue}] {pos}/{len} {msg}").expect("invalid template"));
         pb.set_prefix("Generate_code".to_string());
         let mut out = String::new();
-        out.push_str("use serde::{Serialize, Deserialize};\n");
+        for import in &self.code.imports {out.push_str(&format!("{}\n", import));}
+        let floats = self.float_types();
         // Enums
         if !generate_impl_from {
-            out.push_str("use strum_macros::Display;\n\n");
+            out.push('\n');
+            let enum_default = self.code.enum_default();
             for e in &self.enums {
-                out.push_str(&format!("#[derive(Debug, Serialize, Deserialize, Clone, Display{})]\n", (if e.is_string_enum {", Default"} else {""})));
+                // Default для смешанных скалярных enum’ов реализуется вручную ниже
+                let derives: Vec<&str> = derives(&self.code.enum_derives, floats.contains(&e.name)).filter(|d| e.is_string_enum || *d != "Default").collect();
+                out.push_str(&format!("#[derive({})]\n", derives.join(", ")));
+                // смешанные скалярные значения в JSON лежат как есть, без тега варианта
+                if !e.is_string_enum {out.push_str("#[serde(untagged)]\n");}
+                for attr in &self.code.enum_attributes {out.push_str(&format!("{}\n", attr));}
                 out.push_str(&format!("pub enum {} {{\n", e.name));
-                if e.is_string_enum {out.push_str("\t#[default]\n");}
-                for variant in &e.variants {
-                    let mut var_name = to_upper_camel_case(variant);
+                if e.is_string_enum && enum_default {out.push_str("\t#[default]\n");}
+                // для untagged enum порядок важен: Int раньше Float, иначе целые числа станут f64
+                let mut variants: Vec<&String> = e.variants.iter().collect();
+                if !e.is_string_enum {variants.sort_by_key(|v| SCALAR_VARIANTS.iter().position(|s| s == v));}
+                let mut variant_names = BTreeSet::new();
+                for variant in variants {
+                    let var_name = to_upper_camel_case(variant);
                     if e.is_string_enum {
-                        if !var_name.is_empty() {
-                            if variant.chars().next().map_or(false, |c| c.is_numeric()) {var_name = format!("Enum{}", var_name);}
-                            // старый вариант для строковых enum’ов
-                            out.push_str(&format!("\t#[serde(rename = \"{}\")]\n", variant));
-                            out.push_str(&format!("\t{},\n", var_name));
-                        }
+                        // старый вариант для строковых enum’ов; имя всегда уникально, исходное значение — в rename
+                        let var_name = unique_name(&mut variant_names, variant_ident(var_name), "");
+                        out.push_str(&format!("\t#[serde(rename = {:?})]\n", variant));
+                        out.push_str(&format!("\t{},\n", var_name));
                     } else {
                         // новый вариант для скалярных enum’ов
                         let ty = match variant.as_str() {
@@ -340,12 +543,24 @@ impl Context {
                     }
                     pb.inc(1);
                 }
+                // открытый enum: незнакомое значение не ломает десериализацию, а попадает в Unknown
+                if e.open {
+                    let unknown = unique_name(&mut variant_names, "Unknown".to_string(), "");
+                    out.push_str("\t#[serde(untagged)]\n");
+                    out.push_str(&format!("\t{}(String),\n", unknown));
+                }
                 out.push_str("}\n\n");
                 // implement default for scalar emun
-                if !e.is_string_enum {
+                if !e.is_string_enum && enum_default {
+                    let default = match e.default.as_deref() {
+                        Some("Bool")   => "Bool(false)",
+                        Some("Float")  => "Float(0.0)",
+                        Some("String") => "String(String::new())",
+                        _              => "Int(0)",
+                    };
                     out.push_str(&format!("impl Default for {} {{\n", e.name));
                     out.push_str("\tfn default() -> Self {\n");
-                    out.push_str(&format!("\t\t{}::Int(0)\n", e.name));
+                    out.push_str(&format!("\t\t{}::{}\n", e.name, default));
                     out.push_str("\t}\n");
                     out.push_str("}\n\n");
                 }
@@ -379,14 +594,15 @@ impl Context {
         }
         // Structs
         for s in &self.structs {
-            out.push_str("#[derive(Debug, Serialize, Deserialize, Default, Clone)]\n");
+            out.push_str(&format!("#[derive({})]\n", derives(&self.code.struct_derives, floats.contains(&s.name)).collect::<Vec<_>>().join(", ")));
+            for attr in &self.code.struct_attributes {out.push_str(&format!("{}\n", attr));}
             out.push_str(&format!("pub struct {} {{\n", s.name));
             for f in &s.fields {
                 if f.rename_attr {
                     if f.optional {
-                        out.push_str(&format!("\t#[serde(rename = \"{}\", skip_serializing_if = \"Option::is_none\")]\n", f.original_name));
+                        out.push_str(&format!("\t#[serde(rename = {:?}, skip_serializing_if = \"Option::is_none\")]\n", f.original_name));
                     } else {
-                        out.push_str(&format!("\t#[serde(rename = \"{}\")]\n", f.original_name));
+                        out.push_str(&format!("\t#[serde(rename = {:?})]\n", f.original_name));
                     }
                 } else if f.optional {
                     out.push_str("\t#[serde(skip_serializing_if = \"Option::is_none\")]\n");
@@ -480,10 +696,110 @@ impl Context {
 }
 
 /// Вспомогательная функция: генерирует полный код из параметров
-pub fn generate_structs(root_name: &str, transactions: &[Value], skip_comments: &[&str], rename: &[&str], generate_impl_from: bool, impl_source_object: String, enums_import_path: String, ) -> String {
-    let mut ctx = Context::new(skip_comments, rename);
-    ctx.build_root(&to_upper_camel_case(&capitalize(root_name)), transactions);
-    ctx.generate_code(generate_impl_from, impl_source_object, enums_import_path)
+pub fn generate_structs(root_name: &str, transactions: &[Value], settings: &Settings, generate_impl_from: bool, impl_source_object: String, enums_import_path: String, ) -> String {
+    let mut ctx = Context::new(settings);
+    let root = ctx.build_root(&to_upper_camel_case(&capitalize(root_name)), transactions);
+    let mut code = ctx.generate_code(generate_impl_from, impl_source_object, enums_import_path);
+    if settings.code.tests > 0 {
+        // образцы — те транзакции, что покрывают больше всего структур, optional полей и вариантов enum’ов
+        let features: Vec<BTreeSet<String>> = transactions.iter().map(|v| {
+            let mut features = BTreeSet::new();
+            ctx.collect_features(&root, v, &mut features);
+            features
+        }).collect();
+        let samples: Vec<&Value> = minimal_cover(&features).into_iter().take(settings.code.tests).map(|i| &transactions[i]).collect();
+        let samples = settings.redact_samples(&samples);
+        code.push_str(&round_trip_tests(&root, &samples.iter().collect::<Vec<_>>()));
+    }
+    code
+}
+
+/// Тестовый модуль: каждый образец десериализуется в корневую структуру и сериализуется обратно в тот же JSON (null = отсутствие поля)
+fn round_trip_tests(root: &str, samples: &[&Value]) -> String {
+    let mut out = String::from("#[cfg(test)]\nmod tests {\n");
+    out.push_str("\tuse super::*;\n\n");
+    out.push_str("\tconst SAMPLES: &[&str] = &[\n");
+    for sample in samples {
+        let json = sample.to_string();
+        // raw-строка с числом # больше, чем встречается в образце после кавычки
+        let hashes = "#".repeat((1..).find(|n| !json.contains(&format!("\"{}", "#".repeat(*n)))).unwrap_or(1));
+        out.push_str(&format!("\t\tr{hashes}\"{json}\"{hashes},\n"));
+    }
+    out.push_str("\t];\n\n");
+    out.push_str("\t/// Убирает null-поля и приводит числа к f64: Option::None и 1 / 1.0 не считаются расхождением\n");
+    out.push_str("\tfn normalize(value: serde_json::Value) -> serde_json::Value {\n");
+    out.push_str("\t\tmatch value {\n");
+    out.push_str("\t\t\tserde_json::Value::Object(map) => serde_json::Value::Object(map.into_iter().filter(|(_, v)| !v.is_null()).map(|(k, v)| (k, normalize(v))).collect()),\n");
+    out.push_str("\t\t\tserde_json::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(normalize).collect()),\n");
+    out.push_str("\t\t\tserde_json::Value::Number(n) => n.as_f64().and_then(serde_json::Number::from_f64).map(serde_json::Value::Number).unwrap_or(serde_json::Value::Number(n)),\n");
+    out.push_str("\t\t\tother => other,\n");
+    out.push_str("\t\t}\n");
+    out.push_str("\t}\n\n");
+    out.push_str("\t#[test]\n");
+    out.push_str("\tfn round_trip() {\n");
+    out.push_str("\t\tfor (i, sample) in SAMPLES.iter().enumerate() {\n");
+    out.push_str("\t\t\tlet original: serde_json::Value = serde_json::from_str(sample).expect(\"sample is valid JSON\");\n");
+    out.push_str(&format!("\t\t\tlet parsed: {root} = serde_json::from_str(sample).unwrap_or_else(|e| panic!(\"sample {{i}} does not deserialize into {root}: {{e}}\"));\n"));
+    out.push_str("\t\t\tlet back = serde_json::to_value(&parsed).expect(\"serialize back\");\n");
+    out.push_str("\t\t\tassert_eq!(normalize(back), normalize(original), \"sample {i} changed after round trip\");\n");
+    out.push_str("\t\t}\n");
+    out.push_str("\t}\n");
+    out.push_str("}\n");
+    out
+}
+
+/// Жадное покрытие: каждый раз берём значение, добавляющее больше всего непокрытых признаков (при равенстве — более раннее).
+/// Индексы — в порядке выбора, самые полезные первыми
+pub fn minimal_cover(features: &[BTreeSet<String>]) -> Vec<usize> {
+    let mut uncovered: BTreeSet<&String> = features.iter().flatten().collect();
+    let mut selected = Vec::new();
+    while !uncovered.is_empty() {
+        let Some((best, gain)) = features.iter().enumerate()
+            .map(|(i, f)| (i, f.iter().filter(|x| uncovered.contains(x)).count()))
+            .max_by_key(|(i, gain)| (*gain, std::cmp::Reverse(*i))) else {break};
+        if gain == 0 {break;}
+        for f in &features[best] {uncovered.remove(f);}
+        selected.push(best);
+    }
+    selected
+}
+
+/// Для каждого значения — какие структуры, optional поля (есть / нет) и варианты enum’ов модели `root_name` оно задействует
+pub fn coverage(root_name: &str, values: &[Value], settings: &Settings, ) -> Vec<BTreeSet<String>> {
+    let mut ctx = Context::new(settings);
+    let root = ctx.build_root(&to_upper_camel_case(&capitalize(root_name)), values);
+    values.iter().map(|v| {
+        let mut features = BTreeSet::new();
+        ctx.collect_features(&root, v, &mut features);
+        features
+    }).collect()
+}
+
+/// Порядок вариантов смешанного скалярного enum (порядок попыток десериализации untagged enum)
+const SCALAR_VARIANTS: [&str; 4] = ["Bool", "Int", "Float", "String"];
+
+/// Derive’ы, которые f64 не реализует
+const FLOAT_INCOMPATIBLE: [&str; 3] = ["Eq", "Hash", "Ord"];
+
+/// Derive’ы из настроек; для типа с f64 — без FLOAT_INCOMPATIBLE (в том числе записанных путём, `std::hash::Hash`)
+fn derives(configured: &[String], float: bool) -> impl Iterator<Item = &str> {
+    configured.iter().map(String::as_str).filter(move |d| !float || !FLOAT_INCOMPATIBLE.contains(&d.rsplit("::").next().unwrap_or(d)))
+}
+
+/// Имя варианта смешанного скалярного enum
+fn scalar_variant(st: &ScalarType) -> &'static str {
+    match st {
+        ScalarType::Bool => "Bool",
+        ScalarType::Int => "Int",
+        ScalarType::Float => "Float",
+        ScalarType::String => "String",
+    }
+}
+
+/// Уникальные строковые значения в порядке первого появления
+fn unique_strings(vals: &[&Value]) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    vals.iter().filter_map(|v| v.as_str()).filter(|s| seen.insert(*s)).map(String::from).collect()
 }
 
 fn unique_values_summary(vals: &[&Value]) -> String {
@@ -557,44 +873,99 @@ fn type_to_rust(ft: &FieldType, optional: bool) -> String {
     }
 }
 
-/// Преобразует строку в snake_case
-fn to_snake_case(s: &str) -> String {
-    let mut result = String::new();
-    for (i, ch) in s.chars().enumerate() {
-        if ch.is_uppercase() {
-            if i > 0 {
-                result.push('_');
-            }
-            for lc in ch.to_lowercase() {
-                result.push(lc);
+/// Ключевые слова Rust (включая зарезервированные), которые нельзя использовать как имя поля
+const RUST_KEYWORDS: [&str; 52] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in",
+    "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try", "typeof", "unsized",
+    "virtual", "yield",
+];
+
+/// Делает из snake_case имени допустимое имя поля: ключевые слова → r#type (или self_ для тех, что нельзя сделать raw)
+fn field_ident(name: String) -> String {
+    if name.is_empty() {return "empty".to_string();}
+    if !RUST_KEYWORDS.contains(&name.as_str()) {return name;}
+    if matches!(name.as_str(), "self" | "Self" | "super" | "crate") {format!("{}_", name)} else {format!("r#{}", name)}
+}
+
+/// Делает из UpperCamelCase имени допустимое имя варианта enum
+fn variant_ident(name: String) -> String {
+    if name.is_empty() {
+        "Empty".to_string()
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Enum{}", name)
+    } else if name == "Self" {
+        "SelfValue".to_string()
+    } else {name}
+}
+
+/// Делает из UpperCamelCase имени допустимое имя типа
+fn type_ident(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {format!("Type{}", name)} else {variant_ident(name.to_string())}
+}
+
+/// Имена вариантов enum для строковых значений — по тем же правилам, что и в сгенерированных моделях
+pub fn variant_names(values: &[String]) -> Vec<String> {
+    let mut used = BTreeSet::new();
+    values.iter().map(|v| unique_name(&mut used, variant_ident(to_upper_camel_case(v)), "")).collect()
+}
+
+/// Возвращает первое свободное имя из `name`, `name{sep}2`, `name{sep}3`, ... и занимает его
+fn unique_name(used: &mut BTreeSet<String>, name: String, sep: &str) -> String {
+    let mut candidate = name.clone();
+    let mut n = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{}{}{}", name.strip_suffix(sep).unwrap_or(&name), sep, n);
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+/// Разбивает строку на слова: по небуквенным символам, по переходу к заглавной (`fsWin`),
+/// по концу аббревиатуры (`HTTPServer` → HTTP, Server). Не-ASCII символы кодируются как `u0441`.
+fn split_words(s: &str) -> Vec<String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if !ch.is_ascii() {
+            if !current.is_empty() {words.push(std::mem::take(&mut current));}
+            if ch.is_alphanumeric() {words.push(format!("u{:04x}", ch as u32));}
+            continue;
+        }
+        if !ch.is_ascii_alphanumeric() {
+            if !current.is_empty() {words.push(std::mem::take(&mut current));}
+            continue;
+        }
+        if ch.is_ascii_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|c| c.is_ascii_lowercase());
+            if prev.is_ascii_lowercase() || prev.is_ascii_digit() || (prev.is_ascii_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
             }
-        } else if ch.is_ascii_alphanumeric() {
-            result.push(ch.to_ascii_lowercase());
-        } else {
-            result.push('_');
         }
+        current.push(ch);
     }
-    result
+    if !current.is_empty() {words.push(current);}
+    words
+}
+
+/// Преобразует строку в snake_case
+fn to_snake_case(s: &str) -> String {
+    split_words(s).iter().map(|w| w.to_ascii_lowercase()).collect::<Vec<_>>().join("_")
 }
 
 /// Преобразует строку в UpperCamelCase
 fn to_upper_camel_case(s: &str) -> String {
-    s.split(|c: char| !c.is_ascii_alphanumeric())
-        .filter(|part| !part.is_empty())
-        .map(|part| {
-            let mut chars = part.chars();
-            if let Some(first) = chars.next() {
-                let mut w = String::new();
-                w.extend(first.to_uppercase());
-                for ch in chars {
-                    w.extend(ch.to_lowercase());
-                }
-                w
-            } else {
-                String::new()
-            }
-        })
-        .collect()
+    split_words(s).iter().map(|w| {
+        let lower = w.to_ascii_lowercase();
+        let mut chars = lower.chars();
+        match chars.next() {
+            Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            None => String::new(),
+        }
+    }).collect()
 }
 
 fn capitalize(s: &str) -> String {
@@ -603,4 +974,148 @@ fn capitalize(s: &str) -> String {
         None => String::new(),
         Some(f) => f.to_uppercase().collect::<String>() + chars.as_str(),
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use serde_json::json;
+
+    /// Код модели для поля `v` с заданными значениями
+    fn generate(values: &[Value]) -> String {
+        let transactions: Vec<Value> = values.iter().map(|v| json!({"v": v})).collect();
+        generate_structs("root", &transactions, &Settings::default(), false, String::new(), String::new())
+    }
+
+    /// Объявление enum поля `v` вместе с атрибутами и impl Default
+    fn enum_block(code: &str) -> &str {
+        let start = code.find("#[derive").expect("enum is generated");
+        let root = code.find("pub struct Root").expect("root struct is generated");
+        let end = code[..root].rfind("#[derive").expect("root struct derives");
+        &code[start..end]
+    }
+
+    #[test]
+    fn mixed_scalar_enum_has_only_observed_variants() {
+        let code = generate(&[json!("a"), json!(1.5)]);
+        let block = enum_block(&code);
+        assert!(block.contains("\tFloat(f64),") && block.contains("\tString(String),"), "{block}");
+        assert!(!block.contains("Int(i64)") && !block.contains("Bool(bool)"), "{block}");
+    }
+
+    #[test]
+    fn mixed_scalar_enum_tries_int_before_float() {
+        let code = generate(&[json!(1.5), json!("a"), json!(3)]);
+        let block = enum_block(&code);
+        let int = block.find("\tInt(i64),").expect("Int variant");
+        let float = block.find("\tFloat(f64),").expect("Float variant");
+        assert!(int < float, "{block}");
+    }
+
+    #[test]
+    fn mixed_scalar_enum_is_untagged() {
+        let code = generate(&[json!(true), json!("a")]);
+        let block = enum_block(&code);
+        assert!(block.contains("#[serde(untagged)]\npub enum RootVEnum {"), "{block}");
+    }
+
+    #[test]
+    fn mixed_scalar_enum_defaults_to_observed_type() {
+        let code = generate(&[json!("a"), json!(1.5), json!(2.5)]);
+        assert!(enum_block(&code).contains("RootVEnum::Float(0.0)"), "{code}");
+        let code = generate(&[json!("a"), json!("b"), json!(true)]);
+        assert!(enum_block(&code).contains("RootVEnum::String(String::new())"), "{code}");
+        assert!(!code.contains("Int(0)"), "{code}");
+    }
+
+    /// Объявляет свою копию и её исходный текст: тест ниже сверяет текст со сгенерированным кодом, а остальные десериализуют в копию
+    macro_rules! emitted {
+        ($($item:tt)*) => {
+            $($item)*
+            const EMITTED: &str = stringify!($($item)*);
+        };
+    }
+
+    // то, что генерируется для [1.5, 2.5, "a", 3] с derive’ами из mixed_scalar_settings
+    emitted! {
+        #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+        #[serde(untagged)]
+        pub enum RootVEnum {
+            Int(i64),
+            Float(f64),
+            String(String),
+        }
+
+        impl Default for RootVEnum {
+            fn default() -> Self {
+                RootVEnum::Float(0.0)
+            }
+        }
+    }
+
+    fn mixed_scalar_settings() -> Settings {
+        let mut settings = Settings::default();
+        settings.code.enum_derives = ["Debug", "Serialize", "Deserialize", "Clone", "PartialEq", "Default"].iter().map(|s| s.to_string()).collect();
+        settings
+    }
+
+    #[test]
+    fn mixed_scalar_enum_copy_matches_generated_code() {
+        let transactions: Vec<Value> = [json!(1.5), json!(2.5), json!("a"), json!(3)].iter().map(|v| json!({"v": v})).collect();
+        let code = generate_structs("root", &transactions, &mixed_scalar_settings(), false, String::new(), String::new());
+        let tokens = |code: &str| code.split_whitespace().collect::<String>();
+        assert_eq!(tokens(enum_block(&code)), tokens(EMITTED), "{code}");
+    }
+
+    #[test]
+    fn mixed_scalar_enum_deserializes_integers_as_int() {
+        assert_eq!(serde_json::from_str::<RootVEnum>("3").expect("integer"), RootVEnum::Int(3));
+        assert_eq!(serde_json::from_str::<RootVEnum>("1.5").expect("float"), RootVEnum::Float(1.5));
+    }
+
+    #[test]
+    fn mixed_scalar_enum_deserializes_strings() {
+        assert_eq!(serde_json::from_str::<RootVEnum>(r#""a""#).expect("string"), RootVEnum::String("a".to_string()));
+    }
+
+    #[test]
+    fn mixed_scalar_enum_rejects_unobserved_types() {
+        assert!(serde_json::from_str::<RootVEnum>("true").is_err());
+        assert!(serde_json::from_str::<RootVEnum>("null").is_err());
+    }
+
+    #[test]
+    fn mixed_scalar_enum_default_is_observed_variant() {
+        assert_eq!(RootVEnum::default(), RootVEnum::Float(0.0));
+        // и обратно в JSON значение уходит без тега варианта
+        assert_eq!(serde_json::to_value(RootVEnum::Int(3)).expect("serialize"), json!(3));
+    }
+
+    /// Строка derive перед объявлением `pub {kind} {name} {`
+    fn derive_line<'a>(code: &'a str, declaration: &str) -> &'a str {
+        let at = code.find(declaration).unwrap_or_else(|| panic!("{declaration} is generated: {code}"));
+        let start = code[..at].rfind("#[derive(").expect("derive line");
+        &code[start..start + code[start..].find('\n').expect("derive line end")]
+    }
+
+    #[test]
+    fn float_types_drop_eq_and_hash() {
+        let mut settings = Settings::default();
+        let strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        settings.code.struct_derives = strings(&["Debug", "Clone", "PartialEq", "Eq", "std::hash::Hash"]);
+        settings.code.enum_derives = strings(&["Debug", "Clone", "PartialEq", "Eq", "Hash"]);
+        let transactions = vec![
+            json!({"win": {"amount": 1.5}, "mixed": 1, "plain": {"id": 1}, "kind": "a"}),
+            json!({"win": {"amount": 2.5}, "mixed": 0.5, "plain": {"id": 2}, "kind": "b"}),
+            json!({"win": {"amount": 3.0}, "mixed": "x", "plain": {"id": 3}, "kind": "a"}),
+        ];
+        let code = generate_structs("root", &transactions, &settings, false, String::new(), String::new());
+        // f64 в самой структуре, в содержащей её структуре и в смешанном enum с Float
+        assert_eq!(derive_line(&code, "pub struct Win {"), "#[derive(Debug, Clone, PartialEq)]");
+        assert_eq!(derive_line(&code, "pub struct Root {"), "#[derive(Debug, Clone, PartialEq)]");
+        assert_eq!(derive_line(&code, "pub enum RootMixedEnum {"), "#[derive(Debug, Clone, PartialEq)]");
+        // без f64 derive’ы остаются как в настройках
+        assert_eq!(derive_line(&code, "pub struct Plain {"), "#[derive(Debug, Clone, PartialEq, Eq, std::hash::Hash)]");
+        assert_eq!(derive_line(&code, "pub enum RootKindEnum {"), "#[derive(Debug, Clone, PartialEq, Eq, Hash)]");
+    }
+}
@@ -0,0 +1,109 @@
+//src\discover.rs
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::{set_keys, Configs, GameConfig, };
+use crate::games::select;
+use crate::storage::{list_modes, load_records, };
+
+/// Что есть в данных игры: значение и число транзакций с ним, в порядке первого появления
+#[derive(Debug, Clone, Default)]
+pub struct Discovered {
+    pub modes: Vec<(String, usize)>,
+    pub commands: Option<Vec<(String, usize)>>, // None — в конфиге нет селектора, искать нечем
+    pub actions: Option<Vec<(String, usize)>>,
+}
+
+fn count(found: &mut Vec<(String, usize)>, value: String) {
+    match found.iter_mut().find(|(v, _)| *v == value) {
+        Some((_, n)) => *n += 1,
+        None => found.push((value, 1)),
+    }
+}
+
+impl Discovered {
+    /// Папки `bet_*` и значения селекторов `command` / `action` во всех транзакциях игры
+    pub fn scan(transactions_path: &str, game_config: &GameConfig) -> Self {
+        let mut modes: Vec<(String, usize)> = list_modes(transactions_path).into_iter().map(|m| (m, 0)).collect();
+        let mut commands = game_config.selectors.command.as_ref().map(|_| Vec::new());
+        let mut actions = game_config.selectors.action.as_ref().map(|_| Vec::new());
+        let records = load_records(transactions_path.to_string());
+        for record in &records {
+            let mode = Path::new(&record.file).strip_prefix(transactions_path).ok()
+                .and_then(|p| p.components().next()).and_then(|c| c.as_os_str().to_str()).and_then(|c| c.strip_prefix("bet_"));
+            if let Some((_, n)) = mode.and_then(|mode| modes.iter_mut().find(|(m, _)| m == mode)) {*n += 1;}
+            if let (Some(found), Some(command)) = (commands.as_mut(), select(game_config, &record.value, "command")) {count(found, command);}
+            if let (Some(found), Some(action)) = (actions.as_mut(), select(game_config, &record.value, "action")) {count(found, action);}
+        }
+        Discovered {modes, commands, actions}
+    }
+}
+
+/// Новый список: настроенные значения, которые есть в данных, в прежнем порядке, затем новые в порядке появления
+pub fn updated(configured: &[String], found: &[(String, usize)]) -> Vec<String> {
+    let mut list: Vec<String> = configured.iter().filter(|c| found.iter().any(|(f, _)| f == *c)).cloned().collect();
+    for (value, _) in found {
+        if !list.contains(value) {list.push(value.clone());}
+    }
+    list
+}
+
+fn print_found(title: &str, configured: &[String], found: &[(String, usize)]) {
+    println!("{title}:");
+    for (value, n) in found {
+        let mark = if configured.contains(value) {""} else {"  + new"};
+        println!("\t{value:<30} {n:>8}{mark}");
+    }
+    for value in configured.iter().filter(|c| !found.iter().any(|(f, _)| f == *c)) {
+        println!("\t{value:<30} {:>8}  - not in data", 0);
+    }
+}
+
+/// `discover <provider> <game> [--write]`: режимы, команды и действия из данных; с `--write` — обновляет конфиг игры
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    discover(configs, provider_name, game_name, args.flag("write"))
+}
+
+/// Сравнивает `modes` / `commands` / `actions` конфига игры с данными и печатает разницу; write — записать её в конфиг
+pub fn discover(configs: &Configs, provider_name: &str, game_name: &str, write: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions");
+    if !Path::new(&transactions_path).is_dir() {return Err(format!("{transactions_path} does not exist").into());}
+    let discovered = Discovered::scan(&transactions_path, game_config);
+
+    let mut updates: Vec<(&str, Value)> = Vec::new();
+    let mut changes: Vec<(&str, Vec<String>, Vec<String>)> = Vec::new();
+    let sections = [
+        ("modes", "Modes (bet_* folders)", &game_config.modes, Some(&discovered.modes)),
+        ("commands", "Commands (selectors.command)", &game_config.commands, discovered.commands.as_ref()),
+        ("actions", "Actions (selectors.action)", &game_config.actions, discovered.actions.as_ref()),
+    ];
+    for (key, title, configured, found) in sections {
+        let Some(found) = found else {println!("{title}: no selector in the game config, '{key}' is left as is"); continue;};
+        print_found(title, configured, found);
+        let mut list = updated(configured, found);
+        if key == "modes" {list.sort_by_key(|m| (m.parse::<u64>().unwrap_or(u64::MAX), m.clone()));}
+        if list != *configured {
+            changes.push((key, configured.clone(), list.clone()));
+            updates.push((key, Value::from(list)));
+        }
+    }
+
+    let game_path = configs.game_path(provider_name, game_name);
+    if changes.is_empty() {println!("{} is up to date", game_path.display()); return Ok(());}
+    println!("Changes to {}:", game_path.display());
+    for (key, before, after) in &changes {
+        println!("\t- \"{key}\":{}", Value::from(before.clone()));
+        println!("\t+ \"{key}\":{}", Value::from(after.clone()));
+    }
+    if !write {println!("Run with --write to update {}", game_path.display()); return Ok(());}
+    let text = fs::read_to_string(&game_path)?;
+    let text = set_keys(&text, &updates).map_err(|e| format!("{}: {e}", game_path.display()))?;
+    fs::write(&game_path, text)?;
+    println!("Updated {}", game_path.display());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//src\fixtures.rs
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::{Config, Configs, GameConfig, };
+use crate::convert_to_rust::{coverage, minimal_cover, Settings, };
+use crate::games::select;
+use crate::redact::Redactor;
+use crate::storage::{format_records, list_modes, load_transactions, save_content, };
+
+/// Признаки покрытия каждой транзакции: всё, что её `in` и `out` задействуют в моделях `{root_name}_in` / `{root_name}_out`
+pub fn transaction_features(root_name: &str, transactions: &[&Value], config: &Config, game_config: &GameConfig) -> Vec<BTreeSet<String>> {
+    let mut features = vec![BTreeSet::new(); transactions.len()];
+    for side in ["in", "out"] {
+        let (positions, values): (Vec<usize>, Vec<Value>) = transactions.iter().enumerate().filter_map(|(i, tx)| tx.get(side).map(|v| (i, v.clone()))).unzip();
+        let settings = Settings::from_game_config(config, game_config, side);
+        for (i, set) in positions.into_iter().zip(coverage(&format!("{root_name}{side}"), &values, &settings)) {
+            features[i].extend(set.into_iter().map(|f| format!("{side} {f}")));
+        }
+    }
+    features
+}
+
+/// `fixtures <provider> <game>`: минимальный набор транзакций, покрывающий модели, по режимам и действиям
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions");
+    let output = args.option("output").map(|o| o.to_string()).unwrap_or_else(|| format!("{location}/{provider_name}/{game_name}/fixtures"));
+    let modes = match args.option("mode") {
+        Some(mode) => vec![mode.to_string()],
+        None => list_modes(&transactions_path),
+    };
+    if modes.is_empty() {return Err(format!("No bet_* folders in {transactions_path}").into());}
+    for mode in &modes {
+        let path = format!("{transactions_path}/bet_{mode}");
+        if !Path::new(&path).exists() {println!("Mode {mode}: {path} does not exist"); continue;}
+        let mut transactions = load_transactions(path);
+        // фикстуры попадают в репозиторий, поэтому настроенный redact применяется, если не попросили --raw
+        if let Some(mut redactor) = Redactor::from_game_config(game_config).filter(|_| !args.flag("raw")) {redactor.apply(&mut transactions); redactor.warn_unreadable();}
+        let mut actions: Vec<Option<String>> = Vec::new();
+        for tx in &transactions {
+            let action = select(game_config, tx, "action");
+            if !actions.contains(&action) {actions.push(action);}
+        }
+        for action in actions {
+            let group: Vec<&Value> = transactions.iter().filter(|tx| select(game_config, tx, "action") == action).collect();
+            let action_name = action.clone().unwrap_or_else(|| game_name.to_string());
+            let features = transaction_features(&format!("{action_name}_"), &group, &configs.config, game_config);
+            let mut selected = minimal_cover(&features);
+            selected.sort();
+            let covered: BTreeSet<&String> = features.iter().flatten().collect();
+            save_content(format!("{output}/bet_{mode}/{action_name}.json"), format_records(selected.iter().map(|&i| group[i])));
+            println!("Mode {mode} action {action_name}: {} of {} transactions cover {} features", selected.len(), group.len(), covered.len());
+        }
+    }
+    println!("Fixtures saved to {output}");
+    Ok(())
+}
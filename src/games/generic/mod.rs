@@ -0,0 +1,46 @@
+//src\games\generic\mod.rs
+use serde_json::Value;
+use crate::config::Configs;
+use crate::storage::{load_transactions, save_content, };
+use crate::convert_to_rust::{generate_structs, Settings, };
+use crate::games::select;
+use crate::redact::Redactor;
+
+/// Генерация моделей только по конфигу игры: для игр без своего модуля (например, созданных `new-game`)
+pub async fn execute(configs: &Configs, provider_name: &str, game_name: &str, mode: Option<&str>, command: Option<&str>, action: Option<&str>, output: Option<&str>, ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+
+    let mode_path = if let Some(mode) = mode {format!("/bet_{mode}")} else {"".to_string()};
+    let command_path = if let Some(command) = command {format!("/{command}")} else {"".to_string()};
+    let action_name = if let Some(action) = action {format!("{action}_")} else {format!("{game_name}_")};
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions{mode_path}");
+    let models_path = output.map(|o| o.to_string()).unwrap_or_else(|| format!("{location}/{provider_name}/{game_name}/models"));
+    let mut transactions: Vec<Value> = load_transactions(transactions_path);
+    if let Some(mut redactor) = Redactor::on_load(game_config) {redactor.apply(&mut transactions);}
+    {
+        let ins: Vec<Value> = transactions.iter().filter(|tx| {
+            (select(game_config, tx, "command").as_deref() == command || command.is_none())
+            &&
+            (select(game_config, tx, "action").as_deref() == action || action.is_none())
+        }).filter_map(|tx| tx.get("in").cloned()).collect();
+        let root_name = format!("{action_name}in");
+        let settings = Settings::from_game_config(&configs.config, game_config, "in");
+        let rust_struct = generate_structs(&root_name, &ins, &settings, false, format!("{game_name}_in"), format!("use crate::{game_name}_in::"));
+        let structure_path = format!("{models_path}{mode_path}{command_path}/{root_name}.rs");
+        save_content(structure_path, rust_struct);
+    }
+    {
+        let outs: Vec<Value> = transactions.iter().filter(|tx| {
+            (select(game_config, tx, "command").as_deref() == command || command.is_none())
+            &&
+            (select(game_config, tx, "action").as_deref() == action || action.is_none())
+        }).filter_map(|tx| tx.get("out").cloned()).collect();
+        let root_name = format!("{action_name}out");
+        let settings = Settings::from_game_config(&configs.config, game_config, "out");
+        let rust_struct = generate_structs(&root_name, &outs, &settings, false, format!("{game_name}_out"), format!("use crate::{game_name}_out::"));
+        let structure_path = format!("{models_path}{mode_path}{command_path}/{root_name}.rs");
+        save_content(structure_path, rust_struct);
+    }
+    Ok(())
+}
\ No newline at end of file
@@ -1,6 +1,38 @@
 //src\games\mod.rs
+use serde_json::Value;
+use crate::config::{Configs, GameConfig, };
+use crate::storage::{number_at, string_at, };
 pub mod bng;
 pub mod enjoygaming;
+pub mod generic;
 pub mod hacksawgaming;
 pub mod octoplay;
-pub mod pragmaticplay;
\ No newline at end of file
+pub mod pragmaticplay;
+
+/// Генерация моделей выбранной игры по её конфигу. output — папка моделей вместо `{game}/models`
+pub async fn execute(configs: &Configs, provider_name: &str, game_name: &str, mode: Option<&str>, command: Option<&str>, action: Option<&str>, output: Option<&str>, ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    generic::execute(configs, provider_name, game_name, mode, command, action, output).await
+}
+
+/// Значение селектора транзакции (`command` / `action`) по пути из секции `selectors` конфига игры
+pub fn select(game_config: &GameConfig, tx: &Value, selector: &str) -> Option<String> {
+    let path = match selector {
+        "command" => game_config.selectors.command.as_deref(),
+        "action" => game_config.selectors.action.as_deref(),
+        _ => None,
+    }?;
+    string_at(tx, path)
+}
+
+/// Ставка транзакции: произведение полей `bet.fields` конфига игры (например `in.c` × `in.l`)
+pub fn bet_amount(game_config: &GameConfig, tx: &Value) -> Option<f64> {
+    let fields = &game_config.bet.as_ref()?.fields;
+    if fields.is_empty() {return None;}
+    fields.iter().try_fold(1.0, |acc, path| number_at(tx, path).map(|n| acc * n))
+}
+
+/// Режим ставки (имя папки `bet_{mode}`): ставка × `bet.multiplier`, округлённая до целого
+pub fn bet_mode(game_config: &GameConfig, tx: &Value) -> Option<String> {
+    let multiplier = game_config.bet.as_ref()?.multiplier;
+    bet_amount(game_config, tx).map(|bet| format!("{}", (bet * multiplier).round() as i64))
+}
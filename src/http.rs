@@ -0,0 +1,175 @@
+//src\http.rs
+use serde_json::{Map, Value};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, };
+
+/// Наибольшее тело запроса: Content-Length присылает клиент, и верить ему на слово нельзя
+pub const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// HTTP запрос
+pub struct Request {
+    pub method: String,
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+
+    /// Запрос в виде `in`: параметры строки запроса, поверх них — тело (JSON или form-urlencoded)
+    pub fn to_value(&self) -> Value {
+        let mut params = self.target.split_once('?').map(|(_, q)| parse_form(q)).unwrap_or_default();
+        match parse_body(&String::from_utf8_lossy(&self.body)) {
+            Value::Object(body) => params.extend(body),
+            Value::Null => {}
+            other => return other,
+        }
+        Value::Object(params)
+    }
+}
+
+/// HTTP ответ (тело уже без chunked-кодирования)
+pub struct Response {
+    pub status: String, // "200 OK"
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+}
+
+/// Стартовая строка и заголовки; None — соединение закрыто
+async fn read_head<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<(String, Vec<(String, String)>)>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {return Ok(None);}
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {break;}
+        if let Some((k, v)) = header.split_once(':') {headers.push((k.trim().to_string(), v.trim().to_string()));}
+    }
+    Ok(Some((line.trim_end().to_string(), headers)))
+}
+
+fn content_length(headers: &[(String, String)]) -> Option<usize> {
+    header(headers, "content-length").and_then(|v| v.parse::<usize>().ok())
+}
+
+fn chunked(headers: &[(String, String)]) -> bool {
+    header(headers, "transfer-encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked"))
+}
+
+/// Тело в chunked-кодировке; None — тело больше limit (дальше оно не читается)
+async fn read_chunked<R: AsyncBufRead + Unpin>(reader: &mut R, limit: usize) -> std::io::Result<Option<Vec<u8>>> {
+    let mut body = Vec::new();
+    loop {
+        let mut size = String::new();
+        reader.read_line(&mut size).await?;
+        let size = usize::from_str_radix(size.trim().split(';').next().unwrap_or_default(), 16).unwrap_or(0);
+        if size > limit - body.len() {return Ok(None);}
+        let mut chunk = vec![0; size + 2]; // + \r\n после каждого куска
+        reader.read_exact(&mut chunk).await?;
+        if size == 0 {break;}
+        body.extend_from_slice(&chunk[..size]);
+    }
+    Ok(Some(body))
+}
+
+/// Запрос клиента (chunked-тело уже собрано); на тело больше MAX_BODY отвечает 413 и возвращает ошибку — соединение дальше не читается
+pub async fn read_request<R: AsyncBufRead + AsyncWrite + Unpin>(reader: &mut R) -> std::io::Result<Option<Request>> {
+    let Some((line, headers)) = read_head(reader).await? else {return Ok(None);};
+    let mut parts = line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or_default().to_string(), parts.next().unwrap_or("/").to_string());
+    let body = if chunked(&headers) {
+        read_chunked(reader, MAX_BODY).await?
+    } else {
+        let length = content_length(&headers).unwrap_or(0);
+        if length <= MAX_BODY {
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).await?;
+            Some(body)
+        } else {None}
+    };
+    let Some(body) = body else {
+        reader.write_all(b"HTTP/1.1 413 Payload Too Large\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await?;
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("request body exceeds the {MAX_BODY} bytes limit")));
+    };
+    Ok(Some(Request {method, target, headers, body}))
+}
+
+pub async fn read_response<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<Response>> {
+    let Some((line, headers)) = read_head(reader).await? else {return Ok(None);};
+    let status = line.split_once(' ').map(|(_, s)| s.to_string()).unwrap_or_default();
+    let mut body = Vec::new();
+    if chunked(&headers) {
+        body = read_chunked(reader, usize::MAX).await?.unwrap_or_default();
+    } else if let Some(length) = content_length(&headers) {
+        body.resize(length, 0);
+        reader.read_exact(&mut body).await?;
+    } else {
+        reader.read_to_end(&mut body).await?;
+    }
+    Ok(Some(Response {status, headers, body}))
+}
+
+/// Тело запроса или ответа: JSON, если похоже на JSON, иначе form-urlencoded
+pub fn parse_body(body: &str) -> Value {
+    let trimmed = body.trim();
+    if trimmed.is_empty() {return Value::Null;}
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && let Ok(value) = serde_json::from_str(trimmed) {return value;}
+    Value::Object(parse_form(trimmed))
+}
+
+pub fn parse_form(query: &str) -> Map<String, Value> {
+    query.split('&').filter(|p| !p.is_empty()).map(|pair| {
+        let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+        (percent_decode(k), Value::String(percent_decode(v)))
+    }).collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit() => {
+                let hex = |b: u8| (b as char).to_digit(16).unwrap_or_default() as u8;
+                out.push(hex(bytes[i + 1]) * 16 + hex(bytes[i + 2]));
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn oversized_body_is_rejected_with_413() {
+        let (client, server) = tokio::io::duplex(1024);
+        let (mut client_read, mut client_write) = tokio::io::split(client);
+        client_write.write_all(format!("POST /gs2c HTTP/1.1\r\nContent-Length: {}\r\n\r\n", usize::MAX).as_bytes()).await.expect("write request");
+        let error = read_request(&mut BufReader::new(server)).await.err().expect("oversized request is an error");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        let mut response = String::new();
+        client_read.read_to_string(&mut response).await.expect("read response");
+        assert!(response.starts_with("HTTP/1.1 413 "), "{response}");
+    }
+}
@@ -1,196 +1,123 @@
 //src\main.rs
-use serde_json::Value;
 use std::io::{self, Write};
-use std::fs;
-use std::collections::HashMap;
-use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
-use rustyline::history::History;
 pub mod games;
+pub mod config;
 pub mod storage;
+pub mod cache;
 pub mod convert_to_rust;
+pub mod cli;
+pub mod http;
+pub mod server;
+pub mod recorder;
+pub mod rounds;
+pub mod statistics;
+pub mod transitions;
+pub mod check;
+pub mod balance;
+pub mod redact;
+pub mod fixtures;
+pub mod discover;
+pub mod new_game;
+pub mod prompt;
+pub mod presets;
+pub mod watch;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let config_path = cli::take_config(&mut args);
+    if let Some("help" | "--help" | "-h") = args.first().map(|a| a.as_str()) {println!("{}", cli::USAGE); return Ok(());}
+    let configs = match config::Configs::discover(config_path.as_deref()).map_err(|e| e.to_string()).and_then(|dir| config::Configs::load(&dir).map_err(|e| e.to_string())) {
+        Ok(configs) => configs,
+        Err(errors) => {eprintln!("{errors}"); std::process::exit(1);}
+    };
+    cache::init(format!("{}/temporary/cache", configs.location()));
+    if let Some((command, rest)) = args.split_first() {
+        let args = cli::Args::parse(rest);
+        return match command.as_str() {
+            "serve" => server::run(&args, &configs).await,
+            "record" => recorder::run(&args, &configs).await,
+            "analyze" => statistics::run(&args, &configs).await,
+            "rounds" => rounds::run(&args, &configs).await,
+            "actions" => transitions::run(&args, &configs).await,
+            "check" => check::run(&args, &configs).await,
+            "balance" => balance::run(&args, &configs).await,
+            "redact" => redact::run(&args, &configs).await,
+            "fixtures" => fixtures::run(&args, &configs).await,
+            "discover" => discover::run(&args, &configs).await,
+            "new-game" => new_game::run(&args, &configs).await,
+            "preset" => presets::run(&args, &configs).await,
+            "watch" => watch::run(&args, &configs).await,
+            _ => Err(format!("Unknown command '{command}'\n{}", cli::USAGE).into()),
+        };
+    }
     print!("\x1B[2J\x1B[1;1H"); io::stdout().flush().unwrap();
-    let config: Value = serde_json::from_str(&(fs::read_to_string("./configs/config.json").unwrap_or_default())).unwrap_or_default();
-    let location = config.get("location").and_then(|v| v.as_str()).unwrap_or("./");
+    let location = configs.location();
     tokio::fs::create_dir_all(&format!("{location}/temporary")).await?;
     
-    let all_providers_games: HashMap<String, Vec<String>> = serde_json::from_str(&(fs::read_to_string("./configs/games.json".to_string()).unwrap_or_default())).unwrap_or_default();
+    let presets = presets::Presets::load(&configs)?;
+    if !presets.presets.is_empty() {
+        presets.print();
+        match prompt::choose("Saved presets", "Input preset to run or press enter to choose manually: ", &presets.names(), &format!("{location}/temporary/preset_recent.txt"), false)? {
+            prompt::Answer::Chosen(name) => {
+                if let Err(e) = presets.run(&[name.as_str()], &configs).await {eprintln!("Error running preset '{name}': {e}");}
+                return Ok(());
+            }
+            prompt::Answer::Skipped => {}
+            prompt::Answer::Cancelled => return Ok(()),
+        }
+    }
+
+    let all_providers_games = &configs.games;
 
     let mut supported_providers: Vec<String> = all_providers_games.keys().cloned().collect();
     supported_providers.sort();
-    let mut rp = DefaultEditor::new()?;
-    tokio::fs::create_dir_all(&format!("{location}/temporary/games")).await?;
-    let providers_history_path = &format!("{location}/temporary/games/history.txt");
-    let _ = rp.load_history(providers_history_path);
-    println!("Supported game providers:");
-    for p in &supported_providers {println!("\t- {}", p);}
-    if rp.history().is_empty() {
-        for p in &supported_providers {
-            let _ = rp.add_history_entry(p);
-            let _ = rp.save_history(providers_history_path);
-        }
-    }
-    let provider_name = loop {
-        match rp.readline("Input game provider (required): ") {
-            Ok(line) => {
-                let trimmed = line.trim().to_string();
-                if !trimmed.is_empty() && supported_providers.contains(&trimmed) {
-                    let items: Vec<String> = rp.history().iter().filter(|h| h.as_str() != trimmed).cloned().collect();
-                    let _ = rp.clear_history();
-                    for h in &items {let _ = rp.add_history_entry(h.as_str());}
-                    let _ = rp.save_history(providers_history_path);
-                    let _ = rp.add_history_entry(trimmed.as_str());
-                    if rp.append_history(providers_history_path).is_err() {let _ = rp.save_history(providers_history_path);}
-                    break trimmed;
-                }
-            }
-            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => { return Ok(()); }
-            Err(err) => { eprintln!("Error: {:?}", err); return Ok(()); }
-        }
+    let provider_name = match prompt::choose("Supported game providers", "Input game provider (required): ", &supported_providers, &format!("{location}/temporary/games/recent.txt"), true)? {
+        prompt::Answer::Chosen(provider) => provider,
+        _ => return Ok(()),
     };
 
     let mut supported_games = all_providers_games.get(&provider_name).cloned().unwrap_or_default();
     supported_games.sort();
-    let mut rg = DefaultEditor::new()?;
-    tokio::fs::create_dir_all(&format!("{location}/temporary/games/{provider_name}")).await?;
-    let games_history_path = &format!("{location}/temporary/games/{provider_name}/history.txt");
-    let _ = rg.load_history(games_history_path);
-    println!("Supported games for provider '{}':", provider_name);
-    for g in &supported_games {println!("\t- {}", g);}
-    if rg.history().is_empty() {
-        for g in &supported_games {
-            let _ = rg.add_history_entry(g);
-            let _ = rg.save_history(games_history_path);
-        }
-    }
-    let game_name = loop {
-        match rg.readline("Input game name (required): ") {
-            Ok(line) => {
-                let trimmed = line.trim().to_string();
-                if !trimmed.is_empty() && supported_games.contains(&trimmed) {
-                    let items: Vec<String> = rg.history().iter().filter(|h| h.as_str() != trimmed).cloned().collect();
-                    let _ = rg.clear_history();
-                    for h in &items {let _ = rg.add_history_entry(h.as_str());}
-                    let _ = rg.save_history(games_history_path);
-                    let _ = rg.add_history_entry(trimmed.as_str());
-                    if rg.append_history(games_history_path).is_err() {let _ = rg.save_history(games_history_path);}
-                    break trimmed;
-                }
-            }
-            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => { return Ok(()); }
-            Err(err) => { eprintln!("Error: {:?}", err); return Ok(()); }
-        }
+    let game_name = match prompt::choose(&format!("Supported games for provider '{provider_name}'"), "Input game name (required): ", &supported_games, &format!("{location}/temporary/games/{provider_name}/recent.txt"), true)? {
+        prompt::Answer::Chosen(game) => game,
+        _ => return Ok(()),
     };
 
-    let game_config: Value = serde_json::from_str(&(fs::read_to_string(format!("./configs/games/{provider_name}/{game_name}.json")).unwrap_or_default())).unwrap_or_default();
-    
-    let mut supported_modes: Vec<String> = game_config.get("modes").and_then(|b| b.as_array()).map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect()).unwrap_or_default();
+    let game_config = configs.game(&provider_name, &game_name)?;
+    let recent_dir = format!("{location}/temporary/games/{provider_name}/{game_name}");
+
+    let mut supported_modes: Vec<String> = game_config.modes.clone();
     supported_modes.sort_by_key(|s| s.parse::<u64>().unwrap_or(u64::MAX));
-    let mut rg = DefaultEditor::new()?;
-    tokio::fs::create_dir_all(&format!("{location}/temporary/games/{provider_name}/{game_name}")).await?;
-    let modes_history_path = &format!("{location}/temporary/games/{provider_name}/{game_name}/mode_history.txt");
-    let _ = rg.load_history(modes_history_path);
-    println!("Supported modes for game '{}':", game_name);
-    for m in &supported_modes {println!("\t- {}", m);}
-    if rg.history().is_empty() {
-        for m in &supported_modes {
-            let _ = rg.add_history_entry(m);
-            let _ = rg.save_history(modes_history_path);
-        }
-    }
-    let mode = loop {
-        match rg.readline("Input mode or press enter to skip: ") {
-            Ok(line) => {
-                let trimmed = line.trim().to_string();
-                if trimmed.is_empty() {break None;} 
-                if supported_modes.contains(&trimmed) {
-                    let items: Vec<String> = rg.history().iter().filter(|h| h.as_str() != trimmed).cloned().collect();
-                    let _ = rg.clear_history();
-                    for h in &items {let _ = rg.add_history_entry(h.as_str());}
-                    let _ = rg.save_history(modes_history_path);
-                    let _ = rg.add_history_entry(trimmed.as_str());
-                    if rg.append_history(modes_history_path).is_err() {let _ = rg.save_history(modes_history_path);}
-                    break Some(trimmed);
-                }
-            }
-            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => { return Ok(()); }
-            Err(err) => { eprintln!("Error: {:?}", err); return Ok(()); }
-        }
+    let mode = match prompt::choose(&format!("Supported modes for game '{game_name}'"), "Input mode or press enter to skip: ", &supported_modes, &format!("{recent_dir}/mode_recent.txt"), false)? {
+        prompt::Answer::Chosen(mode) => Some(mode),
+        prompt::Answer::Skipped => None,
+        prompt::Answer::Cancelled => return Ok(()),
     };
-    
-    let mut supported_commands: Vec<String> = game_config.get("commands").and_then(|b| b.as_array()).map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect()).unwrap_or_default();
+
+    let mut supported_commands: Vec<String> = game_config.commands.clone();
     supported_commands.sort();
-    let mut rg = DefaultEditor::new()?;
-    let commands_history_path = &format!("{location}/temporary/games/{provider_name}/{game_name}/command_history.txt");
-    let _ = rg.load_history(commands_history_path);
-    println!("Supported commands for game '{}':", game_name);
-    for m in &supported_commands {println!("\t- {}", m);}
-    if rg.history().is_empty() {
-        for m in &supported_commands {
-            let _ = rg.add_history_entry(m);
-            let _ = rg.save_history(commands_history_path);
-        }
-    }
-    let command = loop {
-        match rg.readline("Input command or press enter to skip: ") {
-            Ok(line) => {
-                let trimmed = line.trim().to_string();
-                if trimmed.is_empty() {break None;} 
-                if supported_commands.contains(&trimmed) {
-                    let items: Vec<String> = rg.history().iter().filter(|h| h.as_str() != trimmed).cloned().collect();
-                    let _ = rg.clear_history();
-                    for h in &items {let _ = rg.add_history_entry(h.as_str());}
-                    let _ = rg.save_history(commands_history_path);
-                    let _ = rg.add_history_entry(trimmed.as_str());
-                    if rg.append_history(commands_history_path).is_err() {let _ = rg.save_history(commands_history_path);}
-                    break Some(trimmed);
-                }
-            }
-            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => { return Ok(()); }
-            Err(err) => { eprintln!("Error: {:?}", err); return Ok(()); }
-        }
+    let command = match prompt::choose(&format!("Supported commands for game '{game_name}'"), "Input command or press enter to skip: ", &supported_commands, &format!("{recent_dir}/command_recent.txt"), false)? {
+        prompt::Answer::Chosen(command) => Some(command),
+        prompt::Answer::Skipped => None,
+        prompt::Answer::Cancelled => return Ok(()),
     };
-    
-    let mut supported_actions: Vec<String> = game_config.get("actions").and_then(|b| b.as_array()).map(|a| a.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect()).unwrap_or_default();
+
+    let mut supported_actions: Vec<String> = game_config.actions.clone();
     supported_actions.sort();
-    let mut rg = DefaultEditor::new()?;
-    let actions_history_path = &format!("{location}/temporary/games/{provider_name}/{game_name}/action_history.txt");
-    let _ = rg.load_history(actions_history_path);
-    println!("Supported actions for game '{}':", game_name);
-    for m in &supported_actions {println!("\t- {}", m);}
-    if rg.history().is_empty() {
-        for m in &supported_actions {
-            let _ = rg.add_history_entry(m);
-            let _ = rg.save_history(actions_history_path);
-        }
-    }
-    let action = loop {
-        match rg.readline("Input action or press enter to skip: ") {
-            Ok(line) => {
-                let trimmed = line.trim().to_string();
-                if trimmed.is_empty() {break None;} 
-                if supported_actions.contains(&trimmed) {
-                    let items: Vec<String> = rg.history().iter().filter(|h| h.as_str() != trimmed).cloned().collect();
-                    let _ = rg.clear_history();
-                    for h in &items {let _ = rg.add_history_entry(h.as_str());}
-                    let _ = rg.save_history(actions_history_path);
-                    let _ = rg.add_history_entry(trimmed.as_str());
-                    if rg.append_history(actions_history_path).is_err() {let _ = rg.save_history(actions_history_path);}
-                    break Some(trimmed);
-                }
-            }
-            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => { return Ok(()); }
-            Err(err) => { eprintln!("Error: {:?}", err); return Ok(()); }
-        }
+    let action = match prompt::choose(&format!("Supported actions for game '{game_name}'"), "Input action or press enter to skip: ", &supported_actions, &format!("{recent_dir}/action_recent.txt"), false)? {
+        prompt::Answer::Chosen(action) => Some(action),
+        prompt::Answer::Skipped => None,
+        prompt::Answer::Cancelled => return Ok(()),
     };
 
-    match provider_name.as_str() {
-        "pragmaticplay" => {if let Err(e) = games::pragmaticplay::execute(&provider_name, &game_name, mode.as_deref(), command.as_deref(), action.as_deref()).await {eprintln!("Error executing {provider_name} game {game_name}: {e}");}},
-        "enjoygaming" => {if let Err(e) = games::enjoygaming::execute(&provider_name, &game_name, mode.as_deref(), command.as_deref(), action.as_deref()).await {eprintln!("Error executing {provider_name} game {game_name}: {e}");}},
-        _ => {println!("Provider not implement");}
-    } 
+    if let Err(e) = games::execute(&configs, &provider_name, &game_name, mode.as_deref(), command.as_deref(), action.as_deref(), None).await {eprintln!("Error executing {provider_name} game {game_name}: {e}");}
+
+    if let Some(name) = prompt::input("Save this selection as a preset (name, or press enter to skip): ")? {
+        let mut presets = presets;
+        let count = presets.add(&name, presets::Selection {provider: provider_name, game: game_name, mode, command, action, output: None});
+        presets.save()?;
+        println!("Preset '{name}' has {count} selection(s), run it with 'converter preset run {name}'");
+    }
     Ok(())
 }
\ No newline at end of file
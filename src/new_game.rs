@@ -0,0 +1,124 @@
+//src\new_game.rs
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::{set_keys, Configs, PROVIDER_DEFAULTS, };
+use crate::discover::discover;
+
+/// Конфиг новой игры провайдера без `_provider.json`: селекторы и ставку нужно заполнить самому
+const TEMPLATE: &str = r#"{
+	"modes":[],
+	"commands":[],
+	"actions":[],
+	"selectors":{},
+	"skip_comments":[],
+	"preserve_order":true,
+	"enums":{"min_variants":2, "max_variants":19, "open":true, "paths":{}}
+}
+"#;
+
+/// Конфиг новой игры провайдера с `_provider.json`: всё общее берётся оттуда
+const PROVIDER_TEMPLATE: &str = r#"{
+	"modes":[],
+	"commands":[],
+	"actions":[]
+}
+"#;
+
+/// Добавляет игру в текст `games.json` (формат файла — по строке на игру), провайдера без записи — по алфавиту
+pub fn add_game(text: &str, provider: &str, game: &str) -> String {
+    let mut lines: Vec<String> = text.lines().map(|l| l.to_string()).collect();
+    let provider_line = |l: &str| l.starts_with("    \"") && l.trim_end().ends_with('[');
+    let name_of = |l: &str| l.trim().split('"').nth(1).unwrap_or_default().to_string();
+    match lines.iter().position(|l| provider_line(l) && name_of(l) == provider) {
+        Some(start) => {
+            let end = start + lines[start..].iter().position(|l| l.trim_start().starts_with(']')).unwrap_or(lines.len() - start);
+            let mut games: Vec<String> = lines[start + 1..end].iter().map(|l| l.trim().trim_end_matches(',').to_string()).filter(|g| !g.is_empty()).collect();
+            games.push(format!("\"{game}\""));
+            let entries: Vec<String> = games.iter().enumerate().map(|(i, g)| format!("        {g}{}", if i + 1 < games.len() {","} else {""})).collect();
+            lines.splice(start + 1..end, entries);
+        }
+        None => {
+            let close = lines.iter().rposition(|l| l.trim() == "}").unwrap_or(lines.len());
+            let before = lines.iter().position(|l| provider_line(l) && name_of(l).as_str() > provider).unwrap_or(close);
+            let block = [format!("    \"{provider}\": ["), format!("        \"{game}\""), format!("    ]{}", if before < close {","} else {""})];
+            // новый провайдер в конце: у предыдущего нужна запятая
+            if before == close && let Some(last) = lines[..close].iter().rposition(|l| l.trim_start().starts_with(']')) && !lines[last].trim_end().ends_with(',') {
+                lines[last] = format!("{},", lines[last].trim_end());
+            }
+            for (i, line) in block.into_iter().enumerate() {lines.insert(before + i, line);}
+        }
+    }
+    let mut result = lines.join("\n");
+    if text.ends_with('\n') {result.push('\n');}
+    result
+}
+
+/// Имя становится папкой (и раньше — модулем): только строчные буквы, цифры и '_'
+fn check_name(kind: &str, name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') || name.starts_with('_') {
+        return Err(format!("Invalid {kind} name '{name}': use lowercase letters, digits and '_' (it becomes a folder name)"));
+    }
+    Ok(())
+}
+
+/// `new-game <provider> <game> [--from <game>]`: запись в games.json, конфиг игры, папка transactions и discover по уже лежащим данным
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    check_name("provider", provider_name)?;
+    check_name("game", game_name)?;
+    if configs.games.get(provider_name).is_some_and(|games| games.iter().any(|g| g == game_name)) {
+        return Err(format!("Game '{game_name}' is already listed for '{provider_name}' in {}", configs.dir.join("games.json").display()).into());
+    }
+    let game_path = configs.game_path(provider_name, game_name);
+    if game_path.exists() {return Err(format!("{} already exists", game_path.display()).into());}
+
+    let content = match args.option("from") {
+        Some(source) => {
+            configs.game(provider_name, source)?;
+            let source_path = configs.game_path(provider_name, source);
+            let text = fs::read_to_string(&source_path)?;
+            let source_config: Value = serde_json::from_str(&text).map_err(|e| format!("{}: {e}", source_path.display()))?;
+            // режимы, команды и действия у новой игры свои — их заполнит discover
+            let mut updates = vec![("modes", Value::Array(Vec::new())), ("commands", Value::Array(Vec::new())), ("actions", Value::Array(Vec::new()))];
+            // порты исходной игры заняты ею самой: serve и record обеих игр не должны сталкиваться
+            for section in ["serve", "record"] {
+                if let Some(Value::Object(mut settings)) = source_config.get(section).cloned() && settings.remove("port").is_some() {updates.push((section, Value::Object(settings)));}
+            }
+            set_keys(&text, &updates).map_err(|e| format!("{}: {e}", source_path.display()))?
+        }
+        None if game_path.with_file_name(PROVIDER_DEFAULTS).is_file() => PROVIDER_TEMPLATE.to_string(),
+        None => TEMPLATE.to_string(),
+    };
+    let games_path = configs.dir.join("games.json");
+    let games_text = fs::read_to_string(&games_path)?;
+    let game_dir = game_path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let new_dir = !game_dir.exists();
+    // перечитываем конфиги: так новая игра проходит ту же проверку, что и остальные; не прошла — записанное откатывается
+    let registered = fs::create_dir_all(&game_dir)
+        .and_then(|_| fs::write(&game_path, &content))
+        .and_then(|_| fs::write(&games_path, add_game(&games_text, provider_name, game_name)))
+        .map_err(|e| e.to_string())
+        .and_then(|_| Configs::load(&configs.dir).map_err(|e| e.to_string()));
+    let configs = match registered {
+        Ok(configs) => configs,
+        Err(e) => {
+            let _ = fs::write(&games_path, &games_text);
+            let _ = fs::remove_file(&game_path);
+            if new_dir {let _ = fs::remove_dir(&game_dir);}
+            return Err(format!("Game '{game_name}' was not added: {e}").into());
+        }
+    };
+    println!("Created {}", game_path.display());
+    println!("Added '{game_name}' to '{provider_name}' in {}", games_path.display());
+
+    let transactions_path = format!("{}/{provider_name}/{game_name}/transactions", configs.location());
+    fs::create_dir_all(&transactions_path)?;
+    println!("Transactions go to {transactions_path}/bet_{{mode}}");
+
+    let has_data = fs::read_dir(&transactions_path)?.filter_map(Result::ok).any(|e| e.path().is_dir() || e.path().extension().is_some_and(|x| x == "json"));
+    if has_data {discover(&configs, provider_name, game_name, true)?;} else {println!("No data yet: record transactions, then run 'converter discover {provider_name} {game_name} --write'");}
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//src\presets.rs
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::Configs;
+use crate::games;
+
+/// Один выбор интерактивного меню: игра, фильтры и куда сохранить модели
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Selection {
+    pub provider: String,
+    pub game: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>, // папка моделей вместо `{location}/{provider}/{game}/models`
+}
+
+impl Selection {
+    pub fn describe(&self) -> String {
+        let mut text = format!("{}/{}", self.provider, self.game);
+        for (name, value) in [("mode", &self.mode), ("command", &self.command), ("action", &self.action), ("output", &self.output)] {
+            if let Some(value) = value {text.push_str(&format!(" {name}={value}"));}
+        }
+        text
+    }
+
+    pub async fn execute(&self, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        configs.game(&self.provider, &self.game)?;
+        games::execute(configs, &self.provider, &self.game, self.mode.as_deref(), self.command.as_deref(), self.action.as_deref(), self.output.as_deref()).await
+    }
+}
+
+/// Именованные наборы выборов в `{location}/temporary/presets.json`; выборы пресета выполняются по порядку
+#[derive(Debug, Clone, Default)]
+pub struct Presets {
+    pub path: String,
+    pub presets: BTreeMap<String, Vec<Selection>>,
+}
+
+impl Presets {
+    pub fn load(configs: &Configs) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = format!("{}/temporary/presets.json", configs.location());
+        let presets = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| format!("{path}: {e}"))?,
+            Err(_) => BTreeMap::new(),
+        };
+        Ok(Presets {path, presets})
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(dir) = Path::new(&self.path).parent() {fs::create_dir_all(dir)?;}
+        fs::write(&self.path, serde_json::to_string_pretty(&self.presets)?)?;
+        Ok(())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.presets.keys().cloned().collect()
+    }
+
+    /// Добавляет выбор в конец пресета (новый пресет — если такого имени нет)
+    pub fn add(&mut self, name: &str, selection: Selection) -> usize {
+        let selections = self.presets.entry(name.to_string()).or_default();
+        if !selections.contains(&selection) {selections.push(selection);}
+        selections.len()
+    }
+
+    /// Выполняет выборы пресетов по порядку; ошибка одного выбора не останавливает остальные
+    pub async fn run(&self, names: &[&str], configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut failed = 0;
+        for name in names {
+            let selections = self.presets.get(*name).ok_or_else(|| format!("No preset '{name}' in {}, known: {}", self.path, self.names().join(", ")))?;
+            for (i, selection) in selections.iter().enumerate() {
+                println!("Preset '{name}' {}/{}: {}", i + 1, selections.len(), selection.describe());
+                if let Err(e) = selection.execute(configs).await {
+                    eprintln!("Error executing {}: {e}", selection.describe());
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {return Err(format!("{failed} preset selections failed").into());}
+        Ok(())
+    }
+
+    pub fn print(&self) {
+        if self.presets.is_empty() {println!("No presets in {}", self.path); return;}
+        for (name, selections) in &self.presets {
+            println!("{name}:");
+            for selection in selections {println!("\t{}", selection.describe());}
+        }
+    }
+}
+
+/// `preset list | run <name>... | save <name> <provider> <game> [--mode] [--command] [--action] [--output] | delete <name>`
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut presets = Presets::load(configs)?;
+    match args.required(0, "list|run|save|delete")? {
+        "list" => presets.print(),
+        "run" => {
+            let names: Vec<&str> = args.positional.iter().skip(1).map(|s| s.as_str()).collect();
+            if names.is_empty() {args.required(1, "name")?;}
+            presets.run(&names, configs).await?;
+        }
+        "save" => {
+            let name = args.required(1, "name")?;
+            let selection = Selection {
+                provider: args.required(2, "provider")?.to_string(),
+                game: args.required(3, "game")?.to_string(),
+                mode: args.option("mode").map(String::from),
+                command: args.option("command").map(String::from),
+                action: args.option("action").map(String::from),
+                output: args.option("output").map(String::from),
+            };
+            configs.game(&selection.provider, &selection.game)?;
+            let count = presets.add(name, selection);
+            presets.save()?;
+            println!("Preset '{name}' has {count} selection(s), saved to {}", presets.path);
+        }
+        "delete" => {
+            let name = args.required(1, "name")?;
+            if presets.presets.remove(name).is_none() {return Err(format!("No preset '{name}' in {}", presets.path).into());}
+            presets.save()?;
+            println!("Preset '{name}' deleted");
+        }
+        other => return Err(format!("Unknown preset action '{other}', expected list, run, save or delete").into()),
+    }
+    Ok(())
+}
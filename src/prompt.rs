@@ -0,0 +1,160 @@
+//src\prompt.rs
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, DefaultEditor, Editor, Helper};
+
+const RECENT_LIMIT: usize = 20;
+
+/// Ответ на вопрос с выбором
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Chosen(String),
+    Skipped,   // пустой ввод там, где выбор необязателен
+    Cancelled, // Ctrl+C / Ctrl+D
+}
+
+/// Подсказки rustyline по списку вариантов: Tab перебирает нечёткие совпадения, недавние выборы — первыми
+pub struct ChoiceHelper {
+    choices: Vec<String>,
+    recent: Vec<String>, // недавние выборы, самый свежий первым
+    matcher: SkimMatcherV2,
+}
+
+impl ChoiceHelper {
+    pub fn new(choices: Vec<String>, recent: Vec<String>) -> Self {
+        ChoiceHelper {choices, recent, matcher: SkimMatcherV2::default()}
+    }
+
+    /// Варианты, подходящие под ввод: сначала недавние (от свежих), затем по качеству совпадения
+    pub fn rank(&self, input: &str) -> Vec<&String> {
+        let input = input.trim();
+        let mut ranked: Vec<(usize, i64, &String)> = self.choices.iter().filter_map(|choice| {
+            let score = if input.is_empty() {Some(0)} else {self.matcher.fuzzy_match(choice, input)}?;
+            let recency = self.recent.iter().position(|r| r == choice).unwrap_or(usize::MAX);
+            Some((recency, -score, choice))
+        }).collect();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+        ranked.into_iter().map(|(_, _, choice)| choice).collect()
+    }
+
+    /// Похожие варианты для сообщения об ошибке: нечёткие совпадения, а если их нет — близкие по написанию (опечатки)
+    pub fn similar(&self, input: &str) -> Vec<&String> {
+        let ranked = self.rank(input);
+        if !ranked.is_empty() {return ranked;}
+        let input = input.trim().to_lowercase();
+        let mut close: Vec<(f64, &String)> = self.choices.iter().map(|c| (strsim::jaro_winkler(&c.to_lowercase(), &input), c)).filter(|(score, _)| *score > 0.8).collect();
+        close.sort_by(|a, b| b.0.total_cmp(&a.0));
+        close.into_iter().map(|(_, c)| c).collect()
+    }
+
+    /// Точный вариант (без учёта регистра)
+    pub fn resolve(&self, input: &str) -> Option<&String> {
+        let input = input.trim();
+        self.choices.iter().find(|c| *c == input).or_else(|| self.choices.iter().find(|c| c.eq_ignore_ascii_case(input)))
+    }
+}
+
+impl Completer for ChoiceHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let candidates = self.rank(&line[..pos]).into_iter().map(|c| Pair {display: c.clone(), replacement: c.clone()}).collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for ChoiceHelper {
+    type Hint = String;
+
+    /// Дописывает лучший вариант, который начинается с введённого; на пустой строке — последний выбор
+    fn hint(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> Option<String> {
+        if pos < line.len() {return None;}
+        self.rank(line).into_iter().find(|c| c.starts_with(line) && c.len() > line.len()).map(|c| c[line.len()..].to_string())
+    }
+}
+
+impl Highlighter for ChoiceHelper {
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Cow::Owned(format!("\x1b[2m{hint}\x1b[0m"))
+    }
+}
+
+impl Validator for ChoiceHelper {}
+
+impl Helper for ChoiceHelper {}
+
+/// Недавние выборы из файла: по строке на выбор, самый свежий — последней строкой
+fn load_recent(recent_path: &str) -> Vec<String> {
+    let content = fs::read_to_string(recent_path).unwrap_or_default();
+    let mut recent: Vec<String> = content.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+    recent.reverse();
+    let mut unique: Vec<String> = Vec::new();
+    for r in recent {
+        if !unique.contains(&r) {unique.push(r);}
+    }
+    unique
+}
+
+fn save_recent(recent_path: &str, recent: &[String]) {
+    if let Some(dir) = Path::new(recent_path).parent() {let _ = fs::create_dir_all(dir);}
+    let content: String = recent.iter().take(RECENT_LIMIT).rev().map(|r| format!("{r}\n")).collect();
+    let _ = fs::write(recent_path, content);
+}
+
+/// Спрашивает один вариант из списка; недопустимый ввод — сообщение с похожими вариантами и повтор вопроса
+pub fn choose(title: &str, prompt: &str, choices: &[String], recent_path: &str, required: bool) -> Result<Answer, Box<dyn std::error::Error + Send + Sync>> {
+    let recent: Vec<String> = load_recent(recent_path).into_iter().filter(|r| choices.contains(r)).collect();
+    println!("{title}:");
+    for c in choices {println!("\t- {}{}", c, if recent.first() == Some(c) {" (last used)"} else {""});}
+
+    let config = Config::builder().completion_type(CompletionType::Circular).auto_add_history(false).build();
+    let mut editor: Editor<ChoiceHelper, DefaultHistory> = Editor::with_config(config)?;
+    // стрелка вверх листает недавние выборы, самый свежий — первым
+    for r in recent.iter().rev() {let _ = editor.add_history_entry(r.as_str());}
+    editor.set_helper(Some(ChoiceHelper::new(choices.to_vec(), recent.clone())));
+    loop {
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => return Ok(Answer::Cancelled),
+            Err(err) => return Err(err.into()),
+        };
+        let Some(helper) = editor.helper() else {return Ok(Answer::Cancelled)};
+        if line.trim().is_empty() {
+            if required {println!("A value is required, press Tab to cycle through the choices"); continue;}
+            return Ok(Answer::Skipped);
+        }
+        match helper.resolve(&line).cloned() {
+            Some(choice) => {
+                let mut recent = recent.clone();
+                recent.retain(|r| *r != choice);
+                recent.insert(0, choice.clone());
+                save_recent(recent_path, &recent);
+                return Ok(Answer::Chosen(choice));
+            }
+            None => {
+                let similar: Vec<&str> = helper.similar(&line).into_iter().take(5).map(|c| c.as_str()).collect();
+                if similar.is_empty() {println!("Unknown value '{}', press Tab to cycle through the choices", line.trim());}
+                else {println!("Unknown value '{}', did you mean: {}?", line.trim(), similar.join(", "));}
+            }
+        }
+    }
+}
+
+/// Свободный ввод строки; пустая строка, Ctrl+C и Ctrl+D — None
+pub fn input(prompt: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut editor = DefaultEditor::new()?;
+    match editor.readline(prompt) {
+        Ok(line) => Ok(Some(line.trim().to_string()).filter(|l| !l.is_empty())),
+        Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,232 @@
+//src\recorder.rs
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::games::bet_mode;
+use crate::http::{parse_body, read_request, read_response, Request, Response, };
+use crate::storage::format_records;
+
+/// Куда проксировать запросы: `http://host:port/base`
+#[derive(Debug, Clone)]
+pub struct Upstream {
+    host: String,
+    port: u16,
+    base: String,
+}
+
+impl Upstream {
+    pub fn parse(url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| format!("Upstream '{url}' must start with http:// (https is not supported)"))?;
+        let (authority, base) = rest.split_once('/').map(|(a, b)| (a, format!("/{}", b.trim_end_matches('/')))).unwrap_or((rest, String::new()));
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host.to_string(), port.parse::<u16>().map_err(|e| format!("Invalid port in upstream '{url}': {e}"))?),
+            None => (authority.to_string(), 80),
+        };
+        Ok(Upstream {host, port, base})
+    }
+
+    /// Пересылает запрос и читает ответ целиком. Тело уходит с Content-Length, а Accept-Encoding не пересылается: сжатый ответ не записать
+    async fn forward(&self, request: &Request) -> std::io::Result<Response> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        let mut raw = format!("{} {}{} HTTP/1.1\r\nHost: {}:{}\r\n", request.method, self.base, request.target, self.host, self.port);
+        for (k, v) in &request.headers {
+            if !["host", "connection", "content-length", "transfer-encoding", "accept-encoding", "upgrade"].iter().any(|h| k.eq_ignore_ascii_case(h)) {raw.push_str(&format!("{k}: {v}\r\n"));}
+        }
+        raw.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", request.body.len()));
+        stream.write_all(raw.as_bytes()).await?;
+        stream.write_all(&request.body).await?;
+        read_response(&mut BufReader::new(stream)).await?.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "upstream closed the connection without a response"))
+    }
+}
+
+/// Пишет пары `{ "in": ..., "out": ... }` в `transactions/bet_{mode}/record_{start}.json`
+pub struct Recorder {
+    transactions_dir: String,
+    file_name: String,
+    game_config: GameConfig,
+    mode: Option<String>,
+    pending: Vec<Value>, // записи до первой ставки (doInit и т.п.), уходят в папку первого найденного режима
+    count: usize,
+}
+
+impl Recorder {
+    pub fn new(transactions_dir: String, game_config: GameConfig) -> Self {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        Recorder {transactions_dir, file_name: format!("record_{started}.json"), game_config, mode: None, pending: Vec::new(), count: 0}
+    }
+
+    pub fn record(&mut self, record: Value) -> std::io::Result<()> {
+        if let Some(mode) = bet_mode(&self.game_config, &record) {self.mode = Some(mode);}
+        self.pending.push(record);
+        if let Some(mode) = self.mode.clone() {self.flush(&mode)?;}
+        Ok(())
+    }
+
+    /// Дописывает отложенные записи; записи без найденного режима попадают в `bet_unknown`
+    pub fn finish(&mut self) -> std::io::Result<usize> {
+        let mode = self.mode.clone().unwrap_or_else(|| "unknown".to_string());
+        self.flush(&mode)?;
+        Ok(self.count)
+    }
+
+    fn flush(&mut self, mode: &str) -> std::io::Result<()> {
+        if self.pending.is_empty() {return Ok(());}
+        let dir = format!("{}/bet_{mode}", self.transactions_dir);
+        fs::create_dir_all(&dir)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(format!("{dir}/{}", self.file_name))?;
+        file.write_all(format_records(&self.pending).as_bytes())?;
+        self.count += self.pending.len();
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// `record <provider> <game> --upstream http://host:port`: прокси, сохраняющий каждую пару запрос/ответ
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+
+    let upstream = args.option("upstream").or(game_config.record.upstream.as_deref())
+        .ok_or("No upstream: pass --upstream http://host:port or set record.upstream in the game config")?;
+    let upstream = Upstream::parse(upstream)?;
+    let host = args.option("host").unwrap_or("127.0.0.1");
+    let port = match args.option("port") {
+        Some(port) => port.parse::<u16>().map_err(|e| format!("Invalid --port '{port}': {e}"))?,
+        None => game_config.record.port.unwrap_or(8090),
+    };
+    if game_config.bet.is_none() {println!("No 'bet' section in the game config, records will be saved to bet_unknown");}
+
+    let transactions_dir = format!("{location}/{provider_name}/{game_name}/transactions");
+    let recorder = Arc::new(Mutex::new(Recorder::new(transactions_dir.clone(), game_config.clone())));
+    let listener = TcpListener::bind((host, port)).await?;
+    println!("Recording {provider_name}/{game_name}: http://{host}:{port} -> http://{}:{}{} into {transactions_dir} (Ctrl+C to stop)", upstream.host, upstream.port, upstream.base);
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let (recorder, upstream) = (recorder.clone(), upstream.clone());
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, upstream, recorder).await {eprintln!("Connection {peer}: {e}");}
+                });
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    let count = recorder.lock().await.finish()?;
+    println!("Recorded {count} transactions");
+    Ok(())
+}
+
+async fn handle_connection(stream: TcpStream, upstream: Upstream, recorder: Arc<Mutex<Recorder>>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = BufReader::new(stream);
+    while let Some(request) = read_request(&mut reader).await? {
+        let response = match upstream.forward(&request).await {
+            Ok(response) => response,
+            Err(e) => {
+                let body = format!("Upstream error: {e}");
+                reader.get_mut().write_all(format!("HTTP/1.1 502 Bad Gateway\r\nContent-Length: {}\r\n\r\n{body}", body.len()).as_bytes()).await?;
+                continue;
+            }
+        };
+        let encoding = response.header("content-encoding").filter(|e| !e.eq_ignore_ascii_case("identity"));
+        if let Some(encoding) = encoding {
+            println!("{} {} -> {} (not recorded: {encoding} body)", request.method, request.target, response.status);
+        } else if response.status.starts_with('2') {
+            let record = json!({"in": request.to_value(), "out": parse_body(&String::from_utf8_lossy(&response.body))});
+            recorder.lock().await.record(record)?;
+            println!("{} {} -> {}", request.method, request.target, response.status);
+        } else {
+            println!("{} {} -> {} (not recorded)", request.method, request.target, response.status);
+        }
+        let mut head = format!("HTTP/1.1 {}\r\n", response.status);
+        for (k, v) in &response.headers {
+            if !["transfer-encoding", "content-length", "connection"].iter().any(|h| k.eq_ignore_ascii_case(h)) {head.push_str(&format!("{k}: {v}\r\n"));}
+        }
+        head.push_str(&format!("Content-Length: {}\r\n\r\n", response.body.len()));
+        let stream = reader.get_mut();
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&response.body).await?;
+        if request.header("connection").is_some_and(|c| c.eq_ignore_ascii_case("close")) {break;}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use crate::storage::parse_records;
+
+    /// Заглушка upstream: отвечает одним JSON на один запрос и отдаёт полученный запрос
+    async fn stub_upstream(body: &'static str) -> (u16, tokio::task::JoinHandle<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind stub upstream");
+        let port = listener.local_addr().expect("stub address").port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept proxied request");
+            let mut reader = BufReader::new(stream);
+            let request = read_request(&mut reader).await.expect("read proxied request").expect("request");
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+            reader.get_mut().write_all(response.as_bytes()).await.expect("write stub response");
+            request
+        });
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn proxies_and_records_into_bet_mode() {
+        let dir = std::env::temp_dir().join(format!("converter_recorder_{}_{}", std::process::id(), SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default()));
+        let transactions_dir = dir.join("transactions").display().to_string();
+        let game_config: GameConfig = serde_json::from_value(json!({"bet": {"fields": ["in.c", "in.l"], "multiplier": 100}})).expect("game config");
+        let recorder = Arc::new(Mutex::new(Recorder::new(transactions_dir.clone(), game_config)));
+
+        let (upstream_port, upstream_request) = stub_upstream(r#"{"balance":"99.00","na":"s"}"#).await;
+        let upstream = Upstream::parse(&format!("http://127.0.0.1:{upstream_port}/gs2c")).expect("upstream url");
+        let proxy = TcpListener::bind("127.0.0.1:0").await.expect("bind proxy");
+        let proxy_port = proxy.local_addr().expect("proxy address").port();
+        let proxy_recorder = recorder.clone();
+        let proxy_task = tokio::spawn(async move {
+            let (stream, _) = proxy.accept().await.expect("accept client");
+            handle_connection(stream, upstream, proxy_recorder).await.expect("proxy connection");
+        });
+
+        let body = "action=doSpin&c=0.1&l=10";
+        let mut client = TcpStream::connect(("127.0.0.1", proxy_port)).await.expect("connect to proxy");
+        // браузер просит сжатый ответ и шлёт тело кусками
+        let request = format!("POST /gameService?symbol=vs10 HTTP/1.1\r\nHost: localhost\r\nAccept-Encoding: gzip, deflate, br\r\nContent-Type: application/x-www-form-urlencoded\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n", 10, &body[..10], body.len() - 10, &body[10..]);
+        client.write_all(request.as_bytes()).await.expect("send request");
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.expect("read response");
+        proxy_task.await.expect("proxy task");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with(r#"{"balance":"99.00","na":"s"}"#), "{response}");
+        let forwarded = upstream_request.await.expect("stub task");
+        assert_eq!(forwarded.target, "/gs2c/gameService?symbol=vs10");
+        assert_eq!(forwarded.body, body.as_bytes());
+        assert_eq!(forwarded.header("accept-encoding"), None);
+        assert_eq!(forwarded.header("transfer-encoding"), None);
+        assert_eq!(recorder.lock().await.finish().expect("finish"), 1);
+
+        let mode_dir = dir.join("transactions").join("bet_100");
+        let files: Vec<_> = fs::read_dir(&mode_dir).expect("bet_100 is created").filter_map(Result::ok).map(|e| e.path()).collect();
+        assert_eq!(files.len(), 1, "{files:?}");
+        let file = files[0].display().to_string();
+        let mut content = fs::read_to_string(&file).expect("record file");
+        let records = parse_records(&file, &mut content).expect("records parse");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].value, json!({
+            "in": {"symbol": "vs10", "action": "doSpin", "c": "0.1", "l": "10"},
+            "out": {"balance": "99.00", "na": "s"},
+        }));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
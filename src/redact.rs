@@ -0,0 +1,156 @@
+//src\redact.rs
+use serde_json::{Number, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::storage::{format_records, load_records, parse_amount, save_content, };
+
+/// Секция `redact` конфига игры. Пути — как в селекторах, `*` — любой ключ или элемент массива
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    pub pseudonymize: Vec<String>, // заменяются стабильными псевдонимами: одно значение — один псевдоним во всём наборе
+    pub zero: Vec<String>,         // денежные поля, которые обнуляются
+    pub scale: Vec<String>,        // денежные поля, которые умножаются на `factor`
+    pub factor: f64,
+    pub on_load: bool,             // применять при загрузке транзакций для генерации моделей
+    pub unreadable: BTreeMap<String, usize>, // путь -> сколько строк под zero/scale не удалось прочитать как сумму (оставлены как есть)
+    pseudonyms: BTreeMap<String, Value>,
+    counters: BTreeMap<String, u64>,
+}
+
+impl Redactor {
+    pub fn from_game_config(game_config: &GameConfig) -> Option<Self> {
+        let redact = game_config.redact.as_ref()?;
+        Some(Redactor {
+            pseudonymize: redact.pseudonymize.clone(),
+            zero: redact.zero.clone(),
+            scale: redact.scale.clone(),
+            factor: redact.factor.unwrap_or(1.0),
+            on_load: redact.on_load,
+            ..Default::default()
+        })
+    }
+
+    /// Redactor для загрузчика: только если в конфиге игры включён `redact.on_load`
+    pub fn on_load(game_config: &GameConfig) -> Option<Self> {
+        Self::from_game_config(game_config).filter(|r| r.on_load)
+    }
+
+    pub fn apply(&mut self, transactions: &mut [Value]) {
+        for tx in transactions {self.redact(tx);}
+    }
+
+    pub fn redact(&mut self, tx: &mut Value) {
+        for path in self.pseudonymize.clone() {
+            let prefix = path.rsplit('.').find(|p| *p != "*").unwrap_or("value").to_string();
+            for_each_at(tx, &path, &mut |v| *v = self.pseudonym(v, &prefix));
+        }
+        for (path, factor) in self.zero.iter().map(|p| (p, 0.0)).chain(self.scale.iter().map(|p| (p, self.factor))) {
+            for_each_at(tx, path, &mut |v| match scale_amount(v, factor) {
+                Some(scaled) => *v = scaled,
+                None => *self.unreadable.entry(path.clone()).or_default() += 1,
+            });
+        }
+    }
+
+    /// Предупреждение о суммах, которые не удалось прочитать: они остались как есть и могут раскрыть реальные значения
+    pub fn warn_unreadable(&self) {
+        for (path, count) in &self.unreadable {println!("Warning: {count} values at '{path}' are not amounts and were left as is, check them before sharing");}
+    }
+
+    /// Один и тот же оригинал получает один и тот же псевдоним, даже под разными путями
+    fn pseudonym(&mut self, value: &Value, prefix: &str) -> Value {
+        let key = match value {
+            Value::Null => return Value::Null,
+            Value::String(s) => format!("s:{s}"),
+            other => format!("v:{other}"),
+        };
+        if let Some(known) = self.pseudonyms.get(&key) {return known.clone();}
+        let counter = self.counters.entry(if value.is_number() {"#".to_string()} else {prefix.to_string()}).or_default();
+        *counter += 1;
+        let pseudonym = match value {
+            Value::Number(_) => Value::Number(Number::from(*counter)),
+            _ => Value::String(format!("{prefix}_{counter}")),
+        };
+        self.pseudonyms.insert(key, pseudonym.clone());
+        pseudonym
+    }
+}
+
+/// Вызывает `f` для каждого значения по пути; `*` раскрывается во все ключи объекта или элементы массива
+pub fn for_each_at(value: &mut Value, path: &str, f: &mut dyn FnMut(&mut Value)) {
+    let (key, rest) = match path.split_once('.') {
+        Some((key, rest)) => (key, Some(rest)),
+        None => (path, None),
+    };
+    let mut visit = |child: &mut Value| match rest {
+        Some(rest) => for_each_at(child, rest, f),
+        None => f(child),
+    };
+    match (value, key) {
+        (Value::Object(map), "*") => map.values_mut().for_each(&mut visit),
+        (Value::Array(arr), "*") => arr.iter_mut().for_each(&mut visit),
+        (Value::Object(map), key) => if let Some(child) = map.get_mut(key) {visit(child)},
+        (Value::Array(arr), key) => if let Some(child) = key.parse::<usize>().ok().and_then(|i| arr.get_mut(i)) {visit(child)},
+        _ => {}
+    }
+}
+
+/// Умножает сумму, сохраняя тип: число остаётся числом, строка — строкой с тем же числом знаков после точки и теми же разделителями тысяч.
+/// None — строка не читается как сумма
+fn scale_amount(value: &Value, factor: f64) -> Option<Value> {
+    match value {
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some(Value::Number(Number::from((n.as_f64().unwrap_or(0.0) * factor).round() as i64))),
+        Value::Number(n) => Some(Number::from_f64(n.as_f64().unwrap_or(0.0) * factor).map(Value::Number).unwrap_or(Value::Null)),
+        Value::String(s) => {
+            let amount = parse_amount(s)?;
+            let decimals = s.trim().split_once('.').map(|(_, d)| d.len()).unwrap_or(0);
+            let scaled = format!("{:.*}", decimals, amount * factor);
+            Some(Value::String(if s.contains(',') {group_thousands(&scaled)} else {scaled}))
+        }
+        other => Some(other.clone()),
+    }
+}
+
+/// `-1234567.50` -> `-1,234,567.50`
+fn group_thousands(amount: &str) -> String {
+    let (sign, digits) = amount.split_at(if amount.starts_with('-') {1} else {0});
+    let (whole, fraction) = digits.split_once('.').map(|(w, f)| (w, format!(".{f}"))).unwrap_or((digits, String::new()));
+    let mut grouped = String::new();
+    for (i, c) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {grouped.push(',');}
+        grouped.push(c);
+    }
+    format!("{sign}{grouped}{fraction}")
+}
+
+/// `redact <provider> <game>`: очищенная копия папки transactions
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    let mut redactor = Redactor::from_game_config(game_config).ok_or_else(|| format!("No 'redact' section in {}", configs.game_path(provider_name, game_name).display()))?;
+
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions");
+    let output = args.option("output").map(|o| o.to_string()).unwrap_or_else(|| format!("{location}/{provider_name}/{game_name}/redacted/transactions"));
+    if Path::new(&output).starts_with(&transactions_path) {return Err(format!("Output {output} is inside {transactions_path}").into());}
+    let mode_path = if let Some(mode) = args.option("mode") {format!("/bet_{mode}")} else {"".to_string()};
+    // записи — в порядке файлов, так что псевдонимы при повторном запуске раздаются те же
+    let records = load_records(format!("{transactions_path}{mode_path}"));
+
+    let mut files: Vec<(String, Vec<Value>)> = Vec::new();
+    for mut record in records {
+        redactor.redact(&mut record.value);
+        let relative = Path::new(&record.file).strip_prefix(&transactions_path).map(|p| p.display().to_string()).unwrap_or_else(|_| record.file.clone());
+        match files.last_mut() {
+            Some((file, values)) if *file == relative => values.push(record.value),
+            _ => files.push((relative, vec![record.value])),
+        }
+    }
+    for (file, values) in &files {save_content(format!("{output}/{}", file.trim_start_matches('/')), format_records(values));}
+    println!("Redacted {} files into {output} ({} pseudonyms)", files.len(), redactor.pseudonyms.len());
+    redactor.warn_unreadable();
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//src\rounds.rs
+use serde_json::Value;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::games::{bet_amount, select};
+use crate::storage::{list_modes, load_transactions, number_at, string_at, value_at, };
+
+/// Секция `rounds` конфига игры: как собрать транзакции в сессии и раунды
+#[derive(Debug, Clone, Default)]
+pub struct RoundSettings {
+    pub session: Vec<String>,                     // пути id сессии, берётся первый найденный (`in.mgckey`, `in.session_id`)
+    pub order: Vec<String>,                       // пути порядкового номера внутри сессии (`in.counter`, `in.request_id`)
+    pub round_id: Vec<String>,                    // пути id раунда, если провайдер его присылает
+    pub start_actions: Vec<String>,               // действия, которые начинают платный раунд
+    pub continue_if_previous: Vec<String>,        // start-действие продолжает раунд, если у предыдущей транзакции есть одно из полей (`out.fs`)
+    pub finished: BTreeMap<String, Vec<Value>>,   // условия завершённого раунда по последней транзакции; null — поля нет
+}
+
+impl RoundSettings {
+    pub fn from_game_config(game_config: &GameConfig) -> Self {
+        let rounds = &game_config.rounds;
+        RoundSettings {
+            session: rounds.session.clone(),
+            order: rounds.order.clone(),
+            round_id: rounds.round_id.clone(),
+            start_actions: rounds.start_actions.clone(),
+            continue_if_previous: rounds.continue_if_previous.clone(),
+            finished: rounds.finished.iter().map(|(path, values)| (path.clone(), values.as_array().cloned().unwrap_or_else(|| vec![values.clone()]))).collect(),
+        }
+    }
+
+    fn first_string(paths: &[String], tx: &Value) -> Option<String> {
+        paths.iter().find_map(|path| string_at(tx, path))
+    }
+
+    /// Id сессии транзакции; без настроенных путей все транзакции — одна сессия
+    pub fn session_of(&self, tx: &Value) -> String {
+        RoundSettings::first_string(&self.session, tx).unwrap_or_default()
+    }
+
+    /// Последняя транзакция раунда удовлетворяет всем условиям `finished`
+    pub fn is_finished(&self, tx: &Value) -> bool {
+        self.finished.iter().all(|(path, values)| {
+            let actual = value_at(tx, path).filter(|v| !v.is_null());
+            values.iter().any(|expected| match (expected, actual) {
+                (Value::Null, None) => true,
+                (_, None) | (Value::Null, _) => false,
+                // "true" и true, "1" и 1 — одно и то же значение
+                (expected, Some(actual)) => expected == actual || string_at(expected, "") == string_at(actual, ""),
+            })
+        })
+    }
+}
+
+/// Один раунд: платная транзакция и все транзакции, продолжающие её (респины, фриспины, collect)
+#[derive(Debug, Clone)]
+pub struct Round<'a> {
+    pub session: String,
+    pub id: Option<String>,
+    pub transactions: Vec<&'a Value>,
+    pub positions: Vec<usize>, // индексы транзакций во входном срезе
+    pub complete: bool,
+    pub interleaved: bool,     // между транзакциями раунда в сессии есть транзакции другого раунда
+}
+
+impl Round<'_> {
+    pub fn first(&self) -> &Value {
+        self.transactions[0]
+    }
+
+    pub fn last(&self) -> &Value {
+        self.transactions[self.transactions.len() - 1]
+    }
+
+    /// Ставка раунда — ставка первой транзакции, продолжение раунда не платное
+    pub fn bet(&self, game_config: &GameConfig) -> Option<f64> {
+        bet_amount(game_config, self.first())
+    }
+}
+
+/// Транзакции по сессиям (в порядке первого появления), внутри сессии — по `order`, при равенстве — по порядку файлов.
+/// Принимает и `Value`, и `Record`, чтобы сохранить файл и номер записи
+pub fn sessions<T: Borrow<Value>>(transactions: &[T], settings: &RoundSettings) -> Vec<(String, Vec<usize>)> {
+    let mut sessions: Vec<(String, Vec<usize>)> = Vec::new();
+    for (i, tx) in transactions.iter().enumerate() {
+        let session = settings.session_of(tx.borrow());
+        match sessions.iter_mut().find(|(s, _)| *s == session) {
+            Some((_, positions)) => positions.push(i),
+            None => sessions.push((session, vec![i])),
+        }
+    }
+    for (_, positions) in &mut sessions {
+        // транзакция без номера остаётся сразу за предыдущей по файлу
+        let mut last = f64::NEG_INFINITY;
+        let mut keyed: Vec<(f64, usize)> = positions.iter().map(|&i| {
+            if let Some(key) = settings.order.iter().find_map(|path| number_at(transactions[i].borrow(), path)) {last = key;}
+            (last, i)
+        }).collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+        *positions = keyed.into_iter().map(|(_, i)| i).collect();
+    }
+    sessions
+}
+
+/// Собирает раунды: по id раунда, если он есть, иначе по start-действиям; транзакции до первого раунда (doInit) не входят никуда
+pub fn build_rounds<'a, T: Borrow<Value>>(transactions: &'a [T], game_config: &GameConfig, settings: &RoundSettings) -> Vec<Round<'a>> {
+    let mut rounds: Vec<Round<'a>> = Vec::new();
+    for (session, positions) in sessions(transactions, settings) {
+        let session_start = rounds.len();
+        let mut by_id: BTreeMap<String, usize> = BTreeMap::new();
+        let mut current: Option<usize> = None;
+        let mut previous: Option<&Value> = None;
+        for &i in &positions {
+            let tx: &'a Value = transactions[i].borrow();
+            let target = if let Some(id) = RoundSettings::first_string(&settings.round_id, tx) {
+                let index = *by_id.entry(id.clone()).or_insert_with(|| {
+                    rounds.push(Round {session: session.clone(), id: Some(id), transactions: Vec::new(), positions: Vec::new(), complete: false, interleaved: false});
+                    rounds.len() - 1
+                });
+                Some(index)
+            } else {
+                let action = select(game_config, tx, "action").unwrap_or_default();
+                let starts = settings.start_actions.contains(&action);
+                let continues = previous.is_some_and(|p| settings.continue_if_previous.iter().any(|path| value_at(p, path).is_some_and(|v| !v.is_null())));
+                let open = current.is_some_and(|r| !settings.is_finished(rounds[r].last()));
+                // без признака продолжения незавершённый раунд продолжается, пока не придёт новая платная транзакция
+                if starts && !(continues || open && settings.continue_if_previous.is_empty()) {
+                    rounds.push(Round {session: session.clone(), id: None, transactions: Vec::new(), positions: Vec::new(), complete: false, interleaved: false});
+                    current = Some(rounds.len() - 1);
+                }
+                current
+            };
+            if let Some(r) = target {
+                rounds[r].transactions.push(tx);
+                rounds[r].positions.push(i);
+                current = Some(r);
+            }
+            previous = Some(tx);
+        }
+        // раунд перемешан, если в сессии между его первой и последней транзакцией встречается чужая
+        let owner: BTreeMap<usize, usize> = rounds.iter().enumerate().skip(session_start).flat_map(|(r, round)| round.positions.iter().map(move |p| (*p, r))).collect();
+        for (r, round) in rounds.iter_mut().enumerate().skip(session_start) {
+            let (first, last) = (round.positions[0], round.positions[round.positions.len() - 1]);
+            let from = positions.iter().position(|p| *p == first).unwrap_or(0);
+            let to = positions.iter().position(|p| *p == last).unwrap_or(from);
+            round.interleaved = positions[from..=to].iter().any(|p| owner.get(p).is_some_and(|other| *other != r));
+            // без ответа последней транзакции раунд не завершён, какими бы ни были условия finished
+            round.complete = round.last().get("out").is_some_and(|out| !out.is_null()) && settings.is_finished(round.last());
+        }
+    }
+    rounds
+}
+
+/// `rounds <provider> <game>`: сессии и раунды по режимам ставки, незавершённые и перемешанные раунды
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    let settings = RoundSettings::from_game_config(game_config);
+    if settings.start_actions.is_empty() && settings.round_id.is_empty() {return Err(format!("No 'rounds.start_actions' or 'rounds.round_id' in {}", configs.game_path(provider_name, game_name).display()).into());}
+    let limit = match args.option("limit") {
+        Some(limit) => limit.parse::<usize>().map_err(|e| format!("Invalid --limit '{limit}': {e}"))?,
+        None => 20,
+    };
+
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions");
+    let modes = match args.option("mode") {
+        Some(mode) => vec![mode.to_string()],
+        None => list_modes(&transactions_path),
+    };
+    if modes.is_empty() {return Err(format!("No bet_* folders in {transactions_path}").into());}
+    for mode in &modes {
+        let path = format!("{transactions_path}/bet_{mode}");
+        if !Path::new(&path).exists() {println!("Mode {mode}: {path} does not exist"); continue;}
+        let transactions = load_transactions(path);
+        let rounds = build_rounds(&transactions, game_config, &settings);
+        let outside = transactions.len() - rounds.iter().map(|r| r.transactions.len()).sum::<usize>();
+        let problems: Vec<&Round> = rounds.iter().filter(|r| !r.complete || r.interleaved).collect();
+        println!("Mode {mode}:");
+        println!("\tsessions:             {}", sessions(&transactions, &settings).len());
+        println!("\trounds:               {}", rounds.len());
+        println!("\tincomplete rounds:    {}", rounds.iter().filter(|r| !r.complete).count());
+        println!("\tinterleaved rounds:   {}", rounds.iter().filter(|r| r.interleaved).count());
+        println!("\toutside of rounds:    {outside}");
+        println!("\tlongest round:        {}", rounds.iter().map(|r| r.transactions.len()).max().unwrap_or(0));
+        for round in problems.iter().take(limit) {
+            let actions: Vec<String> = round.transactions.iter().map(|tx| select(game_config, tx, "action").unwrap_or_else(|| "?".to_string())).collect();
+            let order = settings.order.iter().find_map(|path| string_at(round.first(), path)).unwrap_or_default();
+            let problem = match (round.complete, round.interleaved) {
+                (false, true) => "incomplete, interleaved",
+                (false, false) => "incomplete",
+                _ => "interleaved",
+            };
+            println!("\t\t{problem}: session '{}' round {} at {order}: {}", round.session, round.id.as_deref().unwrap_or("-"), actions.join(" -> "));
+        }
+        if problems.len() > limit {println!("\t\t... and {} more (--limit)", problems.len() - limit);}
+    }
+    Ok(())
+}
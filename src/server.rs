@@ -0,0 +1,269 @@
+//src\server.rs
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::tungstenite::error::ProtocolError;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::http::{parse_body, read_request, };
+use crate::games::select;
+use crate::storage::{load_transactions, string_at, };
+
+/// Как выбирать записанный ответ
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    Sequence, // по очереди среди транзакций с тем же command/action
+    Best,     // транзакция, чей `in` больше всего похож на запрос
+}
+
+/// Формат тела ответа
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    Json,
+    Form, // key=value&key=value, как отвечает pragmaticplay
+}
+
+/// Записанные транзакции, сгруппированные по (command, action) селекторами игры
+pub struct Replay {
+    transactions: Vec<Value>,
+    keys: Vec<(String, String)>,
+    index: BTreeMap<(String, String), Vec<usize>>,
+    cursors: BTreeMap<(String, String), usize>,
+    mode: MatchMode,
+    game_config: GameConfig,
+}
+
+impl Replay {
+    pub fn new(transactions: Vec<Value>, game_config: &GameConfig, mode: MatchMode) -> Self {
+        // отвечать можно только транзакциями, у которых записаны и запрос, и ответ
+        let transactions: Vec<Value> = transactions.into_iter().filter(|tx| tx.get("in").is_some() && tx.get("out").is_some()).collect();
+        let mut keys = Vec::new();
+        let mut index: BTreeMap<(String, String), Vec<usize>> = BTreeMap::new();
+        for (i, tx) in transactions.iter().enumerate() {
+            let key = (select(game_config, tx, "command").unwrap_or_default(), select(game_config, tx, "action").unwrap_or_default());
+            index.entry(key.clone()).or_default().push(i);
+            keys.push(key);
+        }
+        Replay {transactions, keys, index, cursors: BTreeMap::new(), mode, game_config: game_config.clone()}
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+
+    /// Command/action запроса теми же селекторами, что строят индекс, по `{"in": запрос}`; None — селектор читает не запрос (`out.*`) и по запросу не определяется
+    fn requested(&self, request: &Value, selector: &str) -> Option<String> {
+        let path = match selector {
+            "command" => self.game_config.selectors.command.as_deref(),
+            "action" => self.game_config.selectors.action.as_deref(),
+            _ => None,
+        };
+        match path {
+            Some(path) if path != "in" && !path.starts_with("in.") => None,
+            _ => Some(select(&self.game_config, &json!({"in": request}), selector).unwrap_or_default()),
+        }
+    }
+
+    /// Подбирает записанный ответ: command/action берутся из запроса селекторами игры; то, что из запроса не определить, — у самой похожей записи с теми же известными command/action. Затем ответ выбирается по режиму
+    pub fn respond(&mut self, request: &Value) -> Option<(&(String, String), &Value)> {
+        let (command, action) = (self.requested(request, "command"), self.requested(request, "action"));
+        let key = match (command, action) {
+            (Some(command), Some(action)) => (command, action),
+            (command, action) => {
+                let fits = |key: &(String, String)| command.as_ref().is_none_or(|c| *c == key.0) && action.as_ref().is_none_or(|a| *a == key.1);
+                let closest = (0..self.transactions.len()).filter(|&i| fits(&self.keys[i]))
+                    .max_by_key(|&i| (similarity(request, &self.transactions[i]["in"]), std::cmp::Reverse(i)))?;
+                self.keys[closest].clone()
+            }
+        };
+        let candidates = self.index.get(&key)?;
+        let cursor = self.cursors.get(&key).copied().unwrap_or(0) % candidates.len();
+        // перебираем кандидатов начиная с курсора, чтобы при равной похожести ответы шли по порядку
+        let position = match self.mode {
+            MatchMode::Sequence => cursor,
+            MatchMode::Best => (0..candidates.len()).map(|n| (cursor + n) % candidates.len())
+                .max_by_key(|&p| (similarity(request, &self.transactions[candidates[p]]["in"]), std::cmp::Reverse((p + candidates.len() - cursor) % candidates.len())))
+                .unwrap_or(cursor),
+        };
+        self.cursors.insert(key.clone(), position + 1);
+        let tx = &self.transactions[candidates[position]];
+        Some((&self.keys[candidates[position]], &tx["out"]))
+    }
+}
+
+/// Похожесть двух значений: 1 за каждое совпавшее скалярное значение; ключ с другим значением ничего не добавляет
+fn similarity(a: &Value, b: &Value) -> usize {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => a.iter().filter_map(|(k, v)| b.get(k).map(|w| similarity(v, w))).sum(),
+        (Value::Array(a), Value::Array(b)) => a.iter().zip(b).map(|(v, w)| similarity(v, w)).sum(),
+        (a, b) if a == b => 1,
+        // "10" в запросе и 10 в записи — одно и то же значение
+        (Value::String(s), other) | (other, Value::String(s)) if string_at(other, "").as_deref() == Some(s.as_str()) => 1,
+        _ => 0,
+    }
+}
+
+/// `serve <provider> <game>`: отвечает записанными `out` на HTTP и WebSocket запросы
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+
+    let mode_path = if let Some(mode) = args.option("mode") {format!("/bet_{mode}")} else {"".to_string()};
+    let match_mode = match args.option("match") {
+        Some("sequence") => MatchMode::Sequence,
+        Some("best") => MatchMode::Best,
+        Some(other) => return Err(format!("Unknown match mode '{other}', expected sequence or best").into()),
+        None => game_config.serve.match_mode.unwrap_or(MatchMode::Sequence),
+    };
+    let format = game_config.serve.response_format.unwrap_or(ResponseFormat::Json);
+    let host = args.option("host").unwrap_or("127.0.0.1");
+    let port = match args.option("port") {
+        Some(port) => port.parse::<u16>().map_err(|e| format!("Invalid --port '{port}': {e}"))?,
+        None => game_config.serve.port.unwrap_or(8080),
+    };
+
+    let transactions = load_transactions(format!("{location}/{provider_name}/{game_name}/transactions{mode_path}"));
+    let replay = Replay::new(transactions, game_config, match_mode);
+    if replay.is_empty() {return Err(format!("No transactions with both 'in' and 'out' for {provider_name} game {game_name}").into());}
+    println!("Serving {} recorded transactions of {provider_name}/{game_name} on http://{host}:{port} ({match_mode:?})", replay.len());
+    let replay = Arc::new(Mutex::new(replay));
+
+    let listener = TcpListener::bind((host, port)).await?;
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let replay = replay.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, replay, format).await {eprintln!("Connection {peer}: {e}");}
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, replay: Arc<Mutex<Replay>>, format: ResponseFormat) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = BufReader::new(stream);
+    while let Some(request) = read_request(&mut reader).await? {
+        if request.header("upgrade").is_some_and(|u| u.eq_ignore_ascii_case("websocket")) {
+            let key = request.header("sec-websocket-key").ok_or("WebSocket upgrade without Sec-WebSocket-Key")?;
+            let accept = derive_accept_key(key.as_bytes());
+            let mut stream = reader.into_inner();
+            stream.write_all(format!("HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n").as_bytes()).await?;
+            return handle_websocket(WebSocketStream::from_raw_socket(stream, Role::Server, None).await, replay).await;
+        }
+        let payload = request.to_value();
+        let response = {
+            let mut replay = replay.lock().await;
+            replay.respond(&payload).map(|((command, action), out)| {
+                println!("{} {} -> {command}/{action}", request.method, request.target);
+                render(out, format)
+            })
+        };
+        let (status, body) = match response {
+            Some(body) => ("200 OK", body),
+            None => ("404 Not Found", "No recorded transaction matches the request".to_string()),
+        };
+        let content_type = if format == ResponseFormat::Json {"application/json"} else {"text/plain; charset=utf-8"};
+        let stream = reader.get_mut();
+        stream.write_all(format!("HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\n\r\n{body}", body.len()).as_bytes()).await?;
+        if request.header("connection").is_some_and(|c| c.eq_ignore_ascii_case("close")) {break;}
+    }
+    Ok(())
+}
+
+async fn handle_websocket(mut ws: WebSocketStream<TcpStream>, replay: Arc<Mutex<Replay>>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    while let Some(message) = ws.next().await {
+        let message = match message {
+            Ok(message) => message,
+            // клиент просто закрыл соединение
+            Err(WsError::ConnectionClosed | WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => break,
+            Err(e) => return Err(e.into()),
+        };
+        let text = match message {
+            Message::Text(text) => text.to_string(),
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let response = {
+            let mut replay = replay.lock().await;
+            replay.respond(&parse_body(&text)).map(|((command, action), out)| {
+                println!("WS -> {command}/{action}");
+                out.to_string()
+            })
+        };
+        ws.send(Message::text(response.unwrap_or_else(|| "{\"error\":\"No recorded transaction matches the request\"}".to_string()))).await?;
+    }
+    Ok(())
+}
+
+/// Записанный `out` в формате ответа провайдера
+pub fn render(out: &Value, format: ResponseFormat) -> String {
+    match (format, out) {
+        (ResponseFormat::Form, Value::Object(map)) => map.iter().map(|(k, v)| match v {
+            Value::String(s) => format!("{k}={s}"),
+            other => format!("{k}={other}"),
+        }).collect::<Vec<_>>().join("&"),
+        _ => out.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pragmatic_replay(mode: MatchMode) -> Replay {
+        let game_config: GameConfig = serde_json::from_value(json!({"selectors": {"command": "in.command", "action": "in.action"}})).expect("game config");
+        let transactions = vec![
+            json!({"in": {"action": "doInit", "mgckey": "A"}, "out": {"na": "s"}}),
+            json!({"in": {"action": "doSpin", "mgckey": "A", "c": "0.1", "l": "10", "index": "2", "counter": "3"}, "out": {"na": "c", "tw": "5.00"}}),
+            json!({"in": {"action": "doCollect", "mgckey": "B", "index": "3"}, "out": {"na": "s", "balance": "105.00"}}),
+        ];
+        Replay::new(transactions, &game_config, mode)
+    }
+
+    #[test]
+    fn answers_with_the_requested_action() {
+        for mode in [MatchMode::Sequence, MatchMode::Best] {
+            let mut replay = pragmatic_replay(mode);
+            // параметры совпадают со spin, но action — doCollect
+            let request = json!({"action": "doCollect", "mgckey": "A", "c": "0.1", "l": "10", "index": "2", "counter": "3"});
+            let (key, out) = replay.respond(&request).expect("collect response");
+            assert_eq!(key, &(String::new(), "doCollect".to_string()));
+            assert_eq!(out["balance"], "105.00");
+            let (key, _) = replay.respond(&json!({"action": "doInit", "mgckey": "X", "c": "0.1", "l": "10"})).expect("init response");
+            assert_eq!(key.1, "doInit");
+        }
+    }
+
+    #[test]
+    fn unrecorded_action_is_not_answered() {
+        let mut replay = pragmatic_replay(MatchMode::Best);
+        assert!(replay.respond(&json!({"action": "doBonus", "mgckey": "A", "c": "0.1", "l": "10"})).is_none());
+    }
+
+    #[test]
+    fn out_selectors_fall_back_to_the_closest_request() {
+        let game_config: GameConfig = serde_json::from_value(json!({"selectors": {"command": "out.command", "action": "out.context.last_action"}})).expect("game config");
+        let transactions = vec![
+            json!({"in": {"command": "play", "action": {"name": "spin"}}, "out": {"command": "play", "context": {"last_action": "spin"}}}),
+            json!({"in": {"command": "play", "action": {"name": "freespin"}}, "out": {"command": "play", "context": {"last_action": "freespin"}}}),
+        ];
+        let mut replay = Replay::new(transactions, &game_config, MatchMode::Best);
+        let (key, _) = replay.respond(&json!({"command": "play", "action": {"name": "freespin"}})).expect("response");
+        assert_eq!(key, &("play".to_string(), "freespin".to_string()));
+    }
+}
@@ -0,0 +1,177 @@
+//src\statistics.rs
+use serde_json::{json, Value};
+use std::path::Path;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::rounds::{build_rounds, Round, RoundSettings, };
+use crate::storage::{list_modes, load_transactions, number_at, save_content, };
+
+const DEFAULT_BUCKETS: [f64; 9] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 500.0, 1000.0];
+
+/// Секция `stats` конфига игры: откуда брать выигрыш и как делить его на корзины (раунды — из секции `rounds`)
+#[derive(Debug, Clone, Default)]
+pub struct StatsSettings {
+    pub win_fields: Vec<String>,
+    pub cumulative: bool, // поле выигрыша — накопленный выигрыш раунда (`tw`), а не выигрыш одного спина (`w`)
+    pub buckets: Vec<f64>, // границы корзин по множителю выигрыша
+}
+
+impl StatsSettings {
+    pub fn from_game_config(game_config: &GameConfig) -> Self {
+        let stats = &game_config.stats;
+        let mut buckets: Vec<f64> = stats.buckets.as_ref().map(|b| b.iter().copied().filter(|b| *b > 0.0).collect()).unwrap_or_else(|| DEFAULT_BUCKETS.to_vec());
+        buckets.sort_by(|a, b| a.total_cmp(b));
+        buckets.dedup();
+        StatsSettings {
+            win_fields: stats.win.fields.clone(),
+            cumulative: stats.win.cumulative,
+            buckets,
+        }
+    }
+}
+
+/// Статистика одного режима ставки
+#[derive(Debug, Clone, Default)]
+pub struct Statistics {
+    pub transactions: usize,
+    pub rounds: usize,
+    pub incomplete: usize,
+    pub wagered: f64,
+    pub won: f64,
+    pub hits: usize,
+    pub max_multiplier: f64,
+    pub buckets: Vec<(String, usize)>,
+}
+
+impl Statistics {
+    pub fn rtp(&self) -> f64 {
+        if self.wagered > 0.0 {self.won / self.wagered * 100.0} else {0.0}
+    }
+
+    pub fn hit_frequency(&self) -> f64 {
+        if self.rounds > 0 {self.hits as f64 / self.rounds as f64 * 100.0} else {0.0}
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({
+            "transactions": self.transactions,
+            "rounds": self.rounds,
+            "incomplete_rounds": self.incomplete,
+            "total_wagered": self.wagered,
+            "total_won": self.won,
+            "rtp": self.rtp(),
+            "hit_frequency": self.hit_frequency(),
+            "max_win_multiplier": self.max_multiplier,
+            "buckets": self.buckets.iter().map(|(name, count)| json!({"multiplier": name, "rounds": count})).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Выигрыш раунда: сумма полей по всем транзакциям, для накопленного выигрыша — наибольшее значение
+pub fn round_win(settings: &StatsSettings, round: &Round) -> f64 {
+    let values = round.transactions.iter().flat_map(|tx| settings.win_fields.iter().filter_map(|path| number_at(tx, path)));
+    if settings.cumulative {values.fold(0.0, f64::max)} else {values.sum()}
+}
+
+/// Статистика по раундам; раунды без ставки не учитываются, незавершённые (выигрыш ещё неизвестен) — только считаются отдельно
+pub fn analyze(transactions: &[Value], game_config: &GameConfig, round_settings: &RoundSettings, settings: &StatsSettings) -> Statistics {
+    let mut stats = Statistics {transactions: transactions.len(), ..Default::default()};
+    let paid: Vec<(Round, f64)> = build_rounds(transactions, game_config, round_settings).into_iter().filter_map(|round| round.bet(game_config).map(|bet| (round, bet))).collect();
+    stats.incomplete = paid.iter().filter(|(round, _)| !round.complete).count();
+    let rounds: Vec<(f64, f64)> = paid.iter().filter(|(round, _)| round.complete).map(|(round, bet)| (*bet, round_win(settings, round))).collect(); // (ставка, выигрыш)
+
+    let mut counts = vec![0usize; settings.buckets.len() + 2];
+    for (bet, win) in &rounds {
+        stats.wagered += bet;
+        stats.won += win;
+        if *win > 0.0 {stats.hits += 1;}
+        let multiplier = if *bet > 0.0 {win / bet} else {0.0};
+        stats.max_multiplier = stats.max_multiplier.max(multiplier);
+        // 0: без выигрыша, 1: до первой границы, дальше — [граница, следующая)
+        let bucket = if *win <= 0.0 {0} else {1 + settings.buckets.iter().take_while(|b| multiplier >= **b).count()};
+        counts[bucket] += 1;
+    }
+    stats.rounds = rounds.len();
+    let mut names = vec!["0".to_string()];
+    let mut lower = 0.0;
+    for b in &settings.buckets {
+        names.push(format!("{lower}-{b}x"));
+        lower = *b;
+    }
+    names.push(format!("{lower}x+"));
+    stats.buckets = names.into_iter().zip(counts).collect();
+    stats
+}
+
+/// `analyze <provider> <game>`: RTP, частота выигрышей и распределение выигрышей по режимам ставки
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    if game_config.bet.is_none() {return Err(format!("No 'bet' section in {}", configs.game_path(provider_name, game_name).display()).into());}
+    let round_settings = RoundSettings::from_game_config(game_config);
+    if round_settings.start_actions.is_empty() && round_settings.round_id.is_empty() {return Err(format!("No 'rounds.start_actions' or 'rounds.round_id' in {}", configs.game_path(provider_name, game_name).display()).into());}
+    let settings = StatsSettings::from_game_config(game_config);
+    if settings.win_fields.is_empty() {return Err(format!("No 'stats.win.fields' in {}", configs.game_path(provider_name, game_name).display()).into());}
+
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions");
+    let modes = match args.option("mode") {
+        Some(mode) => vec![mode.to_string()],
+        None => list_modes(&transactions_path),
+    };
+    if modes.is_empty() {return Err(format!("No bet_* folders in {transactions_path}").into());}
+
+    let mut report = serde_json::Map::new();
+    for mode in &modes {
+        let path = format!("{transactions_path}/bet_{mode}");
+        if !Path::new(&path).exists() {println!("Mode {mode}: {path} does not exist"); continue;}
+        let stats = analyze(&load_transactions(path), game_config, &round_settings, &settings);
+        println!("Mode {mode}:");
+        println!("\ttransactions:       {}", stats.transactions);
+        println!("\trounds:             {}", stats.rounds);
+        println!("\tincomplete rounds:  {} (not counted)", stats.incomplete);
+        println!("\ttotal wagered:      {:.2}", stats.wagered);
+        println!("\ttotal won:          {:.2}", stats.won);
+        println!("\tRTP:                {:.2}%", stats.rtp());
+        println!("\thit frequency:      {:.2}%", stats.hit_frequency());
+        println!("\tmax win multiplier: {:.2}x", stats.max_multiplier);
+        println!("\twin distribution:");
+        for (name, count) in &stats.buckets {
+            let share = if stats.rounds > 0 {*count as f64 / stats.rounds as f64 * 100.0} else {0.0};
+            println!("\t\t{name:>12}: {count:>8} ({share:.2}%)");
+        }
+        report.insert(mode.clone(), stats.to_value());
+    }
+    if let Some(export) = args.option("export") {
+        save_content(export.to_string(), serde_json::to_string_pretty(&Value::Object(report))?);
+        println!("Statistics saved to {export}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incomplete_rounds_are_not_counted() {
+        let game_config: GameConfig = serde_json::from_value(json!({
+            "selectors": {"action": "in.action"},
+            "bet": {"fields": ["in.c", "in.l"], "multiplier": 100},
+            "rounds": {"start_actions": ["doSpin"], "continue_if_previous": ["out.fs"], "finished": {"out.na": ["s"]}},
+            "stats": {"win": {"fields": ["out.w"]}},
+        })).expect("game config");
+        let transactions = vec![
+            json!({"in": {"action": "doSpin", "c": "0.1", "l": "10"}, "out": {"na": "s", "w": "2.00"}}),
+            // ответ не записан
+            json!({"in": {"action": "doSpin", "c": "0.1", "l": "10"}}),
+            // раунд не закончен: collect не записан
+            json!({"in": {"action": "doSpin", "c": "0.1", "l": "10"}, "out": {"na": "c", "w": "5.00"}}),
+        ];
+        let stats = analyze(&transactions, &game_config, &RoundSettings::from_game_config(&game_config), &StatsSettings::from_game_config(&game_config));
+        assert_eq!((stats.rounds, stats.incomplete, stats.hits), (1, 2, 1));
+        assert_eq!((stats.wagered, stats.won), (1.0, 2.0));
+        assert_eq!(stats.hit_frequency(), 100.0);
+    }
+}
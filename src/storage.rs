@@ -1,46 +1,121 @@
 use serde_json::Value;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf, };
 use std::fs;
 //use std::io;
 use walkdir::WalkDir;
 use indicatif::{ProgressBar, ProgressStyle, };
+use rayon::prelude::*;
+use crate::cache;
 
 pub fn load_transactions (a_location: String, ) -> Vec<Value>{
+    load_records(a_location).into_iter().map(|r| r.value).collect()
+}
+
+/// Транзакция вместе с местом, откуда она загружена
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub file: String,
+    pub index: usize, // номер записи в файле, с нуля
+    pub value: Value,
+}
+
+impl std::borrow::Borrow<Value> for Record {
+    fn borrow(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl Record {
+    pub fn location(&self) -> String {
+        format!("{} record {}", self.file, self.index + 1)
+    }
+}
+
+/// Записи одного файла захвата: объекты через запятую (последняя запятая и всё после последней `}` отбрасываются)
+pub fn parse_records(file: &str, content: &mut String) -> Result<Vec<Record>, serde_json::Error> {
+    if let Some(pos) = content.rfind('}') {content.truncate(pos + 1);}
+    let data: Vec<Value> = serde_json::from_str(&("[".to_owned() + content + "]"))?;
+    Ok(data.into_iter().enumerate().map(|(index, value)| Record {file: file.to_string(), index, value}).collect())
+}
+
+/// Записи в формате файла захвата, который читает `parse_records`: объекты через запятую, по строке на запись
+pub fn format_records<'a>(values: impl IntoIterator<Item = &'a Value>) -> String {
+    values.into_iter().map(|value| format!("{value},\n")).collect()
+}
+
+/// Записи файла захвата: из кэша, если файл не менялся, иначе разбор; вместе с отметкой файла для нового кэша и признаком, что кэш не устарел
+fn load_file(path: &Path, cached: Option<cache::Entry<Value>>) -> Result<(Vec<Record>, Option<cache::Stamp>, bool), String> {
+    let file = path.display().to_string();
+    let from_cache = |entry: cache::Entry<Value>| entry.records.into_iter().enumerate().map(|(index, value)| Record {file: file.clone(), index, value}).collect();
+    // размер и время изменения совпали — файл даже не читается
+    let cached = match cached {
+        Some(entry) if entry.stamp.unchanged(path) => {let stamp = entry.stamp.clone(); return Ok((from_cache(entry), Some(stamp), true));}
+        other => other,
+    };
+    let mut content = fs::read_to_string(path).map_err(|e| format!("Read error in file {file}: {e}"))?;
+    let stamp = if cache::enabled() {cache::Stamp::new(path, &content)} else {None};
+    // время изменения другое, а содержимое то же — записи из кэша, но с новой отметкой
+    if let Some(entry) = cached && let Some(stamp) = &stamp && entry.stamp.same_content(stamp) {
+        return Ok((from_cache(entry), Some(stamp.clone()), false));
+    }
+    let records = parse_records(&file, &mut content).map_err(|e| format!("JSON parse error in file {file}: {e}"))?;
+    Ok((records, stamp, false))
+}
+
+/// Записи папки (или файла): файлы по имени, внутри файла — в порядке записи
+pub fn load_records (a_location: String, ) -> Vec<Record>{
     let transactions_file_path = a_location;
-    let pb_main = ProgressBar::new((2) as u64);
+    let pb_main = ProgressBar::new(2_u64);
     pb_main.set_prefix("Load transactions from ".to_owned() + &transactions_file_path + ": ");
     pb_main.set_style(ProgressStyle::default_bar().template("{prefix} [{bar:100.cyan/blue}] {pos}/{len} {msg}").expect("ProgressBar template error"),);
-    let mut l_transactions: Vec<Value> = Vec::new();
+    let mut l_records: Vec<Record> = Vec::new();
     if Path::new(&transactions_file_path).is_dir() {
-        let total = WalkDir::new(&transactions_file_path).into_iter().filter_map(Result::ok).filter(|e| {e.path().is_file() && e.path().extension().and_then(|s| s.to_str()) == Some("json")}).count() as u64;
-        pb_main.set_length(total);
-        for entry in WalkDir::new(&transactions_file_path).into_iter().filter_map(Result::ok) {
-            let path = entry.path().to_path_buf();
-            if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                let mut content = match std::fs::read_to_string(&path) {
-                    Ok(v) => v,
-                    Err(e) => {println!("Read error in file {}: {}", path.display(), e); pb_main.inc(1); continue;}
-                };
-                if let Some(pos) = content.rfind('}') {content.truncate(pos + 1);}
-                let data: Vec<Value> = match serde_json::from_str(&("[".to_owned() + &content.clone() + "]")) {
-                    Ok(v) => v,
-                    Err(e) => {println!("JSON parse error in file {}: {}", path.display(), e); pb_main.inc(1); continue;}
-                };
-                let filtered_data: Vec<Value> = data.iter().map(|item| item.clone()).collect();
-                l_transactions.extend(filtered_data);
-            }
+        // по именам, а не в порядке листинга ФС: с preserve_order от порядка файлов зависит порядок полей и вариантов в моделях
+        let paths: Vec<PathBuf> = WalkDir::new(&transactions_file_path).sort_by_file_name().into_iter().filter_map(Result::ok).map(|e| e.into_path()).filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("json")).collect();
+        pb_main.set_length(paths.len() as u64);
+        let mut cached = cache::open(&transactions_file_path);
+        let hits: Vec<Option<cache::Entry<Value>>> = paths.iter().map(|p| cached.remove(&p.display().to_string())).collect();
+        let mut changed = !cached.is_empty(); // в кэше остались удалённые файлы
+        // файлы разбираются параллельно (потоков — по числу ядер), а порядок записей остаётся порядком обхода
+        let parsed: Vec<_> = paths.par_iter().zip(hits).map(|(path, hit)| {
+            let result = load_file(path, hit);
             pb_main.inc(1);
+            result
+        }).collect();
+        // число записей — у каждого загруженного файла, иначе записи файлов без отметки сдвинут записи следующих
+        let mut stamps: Vec<(String, usize, Option<cache::Stamp>)> = Vec::new();
+        for (path, result) in paths.iter().zip(parsed) {
+            match result {
+                Ok((records, stamp, hit)) => {
+                    changed |= !hit;
+                    stamps.push((path.display().to_string(), records.len(), stamp));
+                    l_records.extend(records);
+                }
+                Err(e) => {changed = true; println!("{e}");}
+            }
+        }
+        if changed && cache::enabled() {
+            let mut files = BTreeMap::new();
+            let mut rest = l_records.as_slice();
+            for (file, count, stamp) in stamps {
+                let (records, tail) = rest.split_at(count);
+                rest = tail;
+                if let Some(stamp) = stamp {files.insert(file, cache::Entry {stamp, records: records.iter().map(|r| &r.value).collect()});}
+            }
+            cache::save(&transactions_file_path, files);
         }
     } else if Path::new(&transactions_file_path).is_file() {
-        let file_content = fs::read_to_string(transactions_file_path).unwrap_or_default();
+        let file_content = fs::read_to_string(&transactions_file_path).unwrap_or_default();
         pb_main.set_position(1);
-        l_transactions = serde_json::from_str(&file_content).unwrap_or_default();
+        let data: Vec<Value> = serde_json::from_str(&file_content).unwrap_or_default();
+        l_records = data.into_iter().enumerate().map(|(index, value)| Record {file: transactions_file_path.clone(), index, value}).collect();
         pb_main.set_position(2);
     } else {
         println!("Does not exist or is not defined: {}", transactions_file_path);
     }
-    pb_main.finish_with_message(" -> loaded ".to_owned() + &l_transactions.len().to_string() + " transactions");
-    l_transactions
+    pb_main.finish_with_message(" -> loaded ".to_owned() + &l_records.len().to_string() + " transactions");
+    l_records
 }
 
 pub fn save_content (a_location: String, a_content: String, ) {
@@ -49,3 +124,44 @@ pub fn save_content (a_location: String, a_content: String, ) {
     fs::write(path, a_content).unwrap();
 }
 
+/// Режимы ставки, для которых есть папки `bet_{mode}`, числовые — по возрастанию
+pub fn list_modes(transactions_path: &str) -> Vec<String> {
+    let mut modes: Vec<String> = fs::read_dir(transactions_path).map(|dir| dir.filter_map(Result::ok)
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().to_str().and_then(|n| n.strip_prefix("bet_")).map(|m| m.to_string()))
+        .collect()).unwrap_or_default();
+    modes.sort_by_key(|m| (m.parse::<u64>().unwrap_or(u64::MAX), m.clone()));
+    modes
+}
+
+/// Значение по пути вида `out.context.last_action` (индексы массивов — числами: `spins.0.win`)
+pub fn value_at<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').filter(|p| !p.is_empty()).try_fold(value, |v, key| match v {
+        Value::Array(arr) => key.parse::<usize>().ok().and_then(|i| arr.get(i)),
+        _ => v.get(key),
+    })
+}
+
+/// Значение по пути в виде строки (числа и bool — текстом, null / объекты / массивы — None)
+pub fn string_at(value: &Value, path: &str) -> Option<String> {
+    match value_at(value, path)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Число по пути: JSON-число или строка с числом (pragmaticplay присылает суммы строками)
+pub fn number_at(value: &Value, path: &str) -> Option<f64> {
+    match value_at(value, path)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => parse_amount(s),
+        _ => None,
+    }
+}
+
+/// Сумма из строки; запятые — разделители тысяч: `"100,000.00"` -> 100000.0
+pub fn parse_amount(s: &str) -> Option<f64> {
+    s.trim().replace(',', "").parse::<f64>().ok()
+}
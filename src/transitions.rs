@@ -0,0 +1,122 @@
+//src\transitions.rs
+use serde_json::Value;
+use std::collections::BTreeMap;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::convert_to_rust::variant_names;
+use crate::games::select;
+use crate::rounds::{sessions, RoundSettings, };
+use crate::storage::{load_transactions, save_content, };
+
+/// Переходы между действиями: сколько раз за `from` сразу следовало `to` в одной сессии
+#[derive(Debug, Clone, Default)]
+pub struct StateMachine {
+    pub actions: Vec<String>,                            // наблюдаемые действия, затем действия из конфига, которых нет в данных
+    pub initial: BTreeMap<String, usize>,                // первое действие сессии
+    pub transitions: BTreeMap<(String, String), usize>,
+}
+
+impl StateMachine {
+    pub fn mine(transactions: &[Value], game_config: &GameConfig, settings: &RoundSettings) -> Self {
+        let mut machine = StateMachine::default();
+        for (_, positions) in sessions(transactions, settings) {
+            let actions: Vec<String> = positions.iter().filter_map(|&i| select(game_config, &transactions[i], "action")).collect();
+            if let Some(first) = actions.first() {*machine.initial.entry(first.clone()).or_default() += 1;}
+            for pair in actions.windows(2) {*machine.transitions.entry((pair[0].clone(), pair[1].clone())).or_default() += 1;}
+            for action in actions {
+                if !machine.actions.contains(&action) {machine.actions.push(action);}
+            }
+        }
+        for action in &game_config.actions {
+            if !machine.actions.contains(action) {machine.actions.push(action.clone());}
+        }
+        machine
+    }
+
+    /// Действия, которые ни разу не встретились в транзакциях
+    pub fn unseen(&self) -> Vec<&String> {
+        self.actions.iter().filter(|a| !self.initial.contains_key(*a) && !self.transitions.keys().any(|(from, to)| from == *a || to == *a)).collect()
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let ids = variant_names(&self.actions);
+        let id = |action: &str| self.actions.iter().position(|a| a == action).map(|i| ids[i].clone()).unwrap_or_default();
+        let mut out = String::from("stateDiagram-v2\n");
+        for (action, id) in self.actions.iter().zip(&ids) {out.push_str(&format!("    state {:?} as {}\n", action, id));}
+        for (action, count) in &self.initial {out.push_str(&format!("    [*] --> {} : {}\n", id(action), count));}
+        for ((from, to), count) in &self.transitions {out.push_str(&format!("    {} --> {} : {}\n", id(from), id(to), count));}
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph actions {\n    rankdir=LR;\n    \"[*]\" [shape=point];\n");
+        for action in &self.actions {out.push_str(&format!("    {:?};\n", action));}
+        for (action, count) in &self.initial {out.push_str(&format!("    \"[*]\" -> {:?} [label=\"{}\"];\n", action, count));}
+        for ((from, to), count) in &self.transitions {out.push_str(&format!("    {:?} -> {:?} [label=\"{}\"];\n", from, to, count));}
+        out.push_str("}\n");
+        out
+    }
+
+    /// Enum действий с serde-именами из транзакций, стартовые действия и таблица переходов для эмулятора
+    pub fn to_rust(&self, imports: &[String]) -> String {
+        let names = variant_names(&self.actions);
+        let name = |action: &str| self.actions.iter().position(|a| a == action).map(|i| format!("Action::{}", names[i])).unwrap_or_default();
+        let mut out = String::new();
+        for import in imports {out.push_str(&format!("{}\n", import));}
+        out.push_str("\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\n");
+        out.push_str("pub enum Action {\n");
+        for (action, variant) in self.actions.iter().zip(&names) {
+            out.push_str(&format!("\t#[serde(rename = {:?})]\n", action));
+            out.push_str(&format!("\t{},\n", variant));
+        }
+        out.push_str("}\n\n");
+        out.push_str("/// Действия, с которых начинались сессии\n");
+        out.push_str(&format!("pub const INITIAL: &[Action] = &[{}];\n\n", self.initial.keys().map(|a| name(a)).collect::<Vec<_>>().join(", ")));
+        out.push_str("/// Наблюдаемые переходы: (из, в, сколько раз)\n");
+        out.push_str("pub const TRANSITIONS: &[(Action, Action, usize)] = &[\n");
+        for ((from, to), count) in &self.transitions {out.push_str(&format!("\t({}, {}, {}),\n", name(from), name(to), count));}
+        out.push_str("];\n\n");
+        out.push_str("impl Action {\n");
+        out.push_str("\t/// Действия, которые могут следовать за этим\n");
+        out.push_str("\tpub fn next(&self) -> &'static [Action] {\n");
+        out.push_str("\t\tmatch self {\n");
+        for action in &self.actions {
+            let next: Vec<String> = self.transitions.keys().filter(|(from, _)| from == action).map(|(_, to)| name(to)).collect();
+            out.push_str(&format!("\t\t\t{} => &[{}],\n", name(action), next.join(", ")));
+        }
+        out.push_str("\t\t}\n");
+        out.push_str("\t}\n\n");
+        out.push_str("\tpub fn can_follow(&self, previous: Action) -> bool {\n");
+        out.push_str("\t\tprevious.next().contains(self)\n");
+        out.push_str("\t}\n");
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// `actions <provider> <game>`: граф переходов между действиями в Mermaid, Graphviz DOT и Rust
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    if game_config.selectors.action.is_none() {return Err(format!("No 'selectors.action' in {}", configs.game_path(provider_name, game_name).display()).into());}
+
+    let mode_path = if let Some(mode) = args.option("mode") {format!("/bet_{mode}")} else {"".to_string()};
+    let transactions = load_transactions(format!("{location}/{provider_name}/{game_name}/transactions{mode_path}"));
+    let machine = StateMachine::mine(&transactions, game_config, &RoundSettings::from_game_config(game_config));
+    if machine.transitions.is_empty() && machine.initial.is_empty() {return Err(format!("No actions found in transactions of {provider_name} game {game_name}").into());}
+
+    println!("Action transitions:");
+    for (action, count) in &machine.initial {println!("\t{:>20} -> {:<20} {:>8}", "[start]", action, count);}
+    for ((from, to), count) in &machine.transitions {println!("\t{from:>20} -> {to:<20} {count:>8}");}
+    for action in machine.unseen() {println!("\t{action} is configured but never seen");}
+
+    let imports = vec!["use serde::{Serialize, Deserialize};".to_string()];
+    let models_path = format!("{location}/{provider_name}/{game_name}/models{mode_path}");
+    save_content(format!("{models_path}/actions.mmd"), machine.to_mermaid());
+    save_content(format!("{models_path}/actions.dot"), machine.to_dot());
+    save_content(format!("{models_path}/actions.rs"), machine.to_rust(&imports));
+    println!("Saved {models_path}/actions.mmd, actions.dot and actions.rs");
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//src\watch.rs
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use similar::{ChangeTag, TextDiff};
+use walkdir::WalkDir;
+use crate::cli::Args;
+use crate::config::{Configs, GameConfig, };
+use crate::games::{self, select, };
+use crate::storage::parse_records;
+
+/// Размер и время изменения каждого `.json` под папкой transactions
+fn snapshot(transactions_path: &str) -> BTreeMap<String, (u64, SystemTime)> {
+    WalkDir::new(transactions_path).into_iter().filter_map(Result::ok)
+        .filter(|e| e.path().is_file() && e.path().extension().and_then(|s| s.to_str()) == Some("json"))
+        .filter_map(|e| e.metadata().ok().map(|m| (e.path().display().to_string(), (m.len(), m.modified().unwrap_or(SystemTime::UNIX_EPOCH)))))
+        .collect()
+}
+
+/// Что перегенерировать: (режим, команда, действие); None — без фильтра
+type Output = (Option<String>, Option<String>, Option<String>);
+
+/// Уже сгенерированные выходы, которые затрагивают записи изменённого файла: с режимом файла и без режима, с командой и действием записи и без них.
+/// Новые файлы моделей не создаются — только обновляются те, что уже есть
+fn affected(file: &str, transactions_path: &str, models_path: &str, game_name: &str, game_config: &GameConfig) -> Result<BTreeSet<Output>, serde_json::Error> {
+    let mut content = fs::read_to_string(file).unwrap_or_default();
+    let records = parse_records(file, &mut content)?;
+    let mode = Path::new(file).strip_prefix(transactions_path).ok()
+        .and_then(|p| p.components().next()).and_then(|c| c.as_os_str().to_str()).and_then(|c| c.strip_prefix("bet_")).map(String::from);
+    let mut candidates = BTreeSet::new();
+    for record in &records {
+        let command = select(game_config, &record.value, "command");
+        let action = select(game_config, &record.value, "action");
+        for mode in [mode.clone(), None] {
+            for command in [command.clone(), None] {
+                for action in [action.clone(), None] {candidates.insert((mode.clone(), command.clone(), action));}
+            }
+        }
+    }
+    Ok(candidates.into_iter().filter(|output| output_files(models_path, game_name, output).iter().any(|f| Path::new(f).is_file())).collect())
+}
+
+/// Файлы моделей одного выхода, как их называют генераторы игр
+fn output_files(models_path: &str, game_name: &str, (mode, command, action): &Output) -> Vec<String> {
+    let mode_path = mode.as_ref().map(|m| format!("/bet_{m}")).unwrap_or_default();
+    let command_path = command.as_ref().map(|c| format!("/{c}")).unwrap_or_default();
+    let action_name = action.clone().unwrap_or_else(|| game_name.to_string());
+    ["in", "out"].iter().map(|side| format!("{models_path}{mode_path}{command_path}/{action_name}_{side}.rs")).collect()
+}
+
+/// Сгенерированный код без комментариев-примеров `/* значение */`: они меняются с каждым захватом, а схема — нет
+fn schema(code: &str) -> String {
+    code.lines().map(|line| {
+        let mut line = line.to_string();
+        while let Some(start) = line.find(" /*") && let Some(end) = line[start..].find("*/") {line.replace_range(start..start + end + 2, "");}
+        line + "\n"
+    }).collect()
+}
+
+/// Изменения схемы: строки сгенерированного кода, которые появились или пропали
+pub fn print_schema_diff(file: &str, before: Option<&str>, after: &str) {
+    let Some(before) = before else {
+        println!("\t{file}: new, {} structs, {} enums", after.matches("pub struct ").count(), after.matches("pub enum ").count());
+        return;
+    };
+    let (before, after) = (schema(before), schema(after));
+    if before == after {println!("\t{file}: no schema changes"); return;}
+    println!("\t{file}:");
+    for change in TextDiff::from_lines(&before, &after).iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => continue,
+        };
+        println!("\t\t{sign} {}", change.value().trim_end());
+    }
+}
+
+/// `watch <provider> <game> [--interval <seconds>]`: при новых и изменённых файлах захвата перегенерирует затронутые модели и печатает разницу схем
+pub async fn run(args: &Args, configs: &Configs) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let provider_name = args.required(0, "provider")?;
+    let game_name = args.required(1, "game")?;
+    let location = configs.location();
+    let game_config = configs.game(provider_name, game_name)?;
+    let interval = match args.option("interval") {
+        Some(seconds) => seconds.parse::<f64>().map_err(|e| format!("Invalid --interval '{seconds}': {e}"))?,
+        None => 2.0,
+    };
+    let transactions_path = format!("{location}/{provider_name}/{game_name}/transactions");
+    let models_path = format!("{location}/{provider_name}/{game_name}/models");
+    fs::create_dir_all(&transactions_path)?;
+
+    let mut known = snapshot(&transactions_path);
+    let mut unreadable = BTreeMap::new(); // о недописанном файле сообщаем один раз, пока он не изменится
+    println!("Watching {transactions_path} ({} files), every {interval}s (Ctrl+C to stop)", known.len());
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(interval.max(0.1)));
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        let current = snapshot(&transactions_path);
+        let changed: Vec<&String> = current.iter().filter(|(file, state)| known.get(*file) != Some(*state)).map(|(file, _)| file).collect();
+        if changed.is_empty() {continue;}
+
+        let mut outputs = BTreeSet::new();
+        let mut ready = known.clone();
+        let mut parsed = 0;
+        for file in changed {
+            match affected(file, &transactions_path, &models_path, game_name, game_config) {
+                Ok(found) => {outputs.extend(found); ready.insert(file.clone(), current[file]); parsed += 1;}
+                // файл ещё дописывается (recorder) — разберём на следующем шаге
+                Err(e) => if unreadable.insert(file.clone(), current[file]) != Some(current[file]) {println!("Skipping {file} until it is complete: {e}");},
+            }
+        }
+        ready.retain(|file, _| current.contains_key(file));
+        unreadable.retain(|file, _| current.contains_key(file) && !ready.contains_key(file));
+        known = ready;
+        if outputs.is_empty() {
+            if parsed > 0 {println!("No existing models are affected by {parsed} changed files, generate them first to watch them");}
+            continue;
+        }
+
+        println!("Regenerating {} outputs:", outputs.len());
+        for output in &outputs {
+            let files = output_files(&models_path, game_name, output);
+            let before: Vec<Option<String>> = files.iter().map(|f| fs::read_to_string(f).ok()).collect();
+            let (mode, command, action) = output;
+            if let Err(e) = games::execute(configs, provider_name, game_name, mode.as_deref(), command.as_deref(), action.as_deref(), None).await {
+                eprintln!("Error generating {provider_name} game {game_name}: {e}");
+                continue;
+            }
+            for (file, before) in files.iter().zip(before) {
+                let after = fs::read_to_string(file).unwrap_or_default();
+                let relative = file.strip_prefix(&models_path).unwrap_or(file).trim_start_matches('/');
+                print_schema_diff(relative, before.as_deref(), &after);
+            }
+        }
+    }
+    println!("Stopped watching {transactions_path}");
+    Ok(())
+}